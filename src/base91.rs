@@ -0,0 +1,36 @@
+// Base-91 number encoding used by compressed positions, Mic-E altitude
+// and comment telemetry: printable characters `!` to `{`, most
+// significant digit first.
+
+pub(crate) fn decode(b: &[u8]) -> Option<u32> {
+    b.iter().try_fold(0u32, |acc, &c| {
+        if (b'!'..=b'{').contains(&c) {
+            Some(acc * 91 + (c - b'!') as u32)
+        } else {
+            None
+        }
+    })
+}
+
+pub(crate) fn encode(mut v: u32, len: usize) -> String {
+    let mut digits = vec![b'!'; len];
+    for d in digits.iter_mut().rev() {
+        *d = b'!' + (v % 91) as u8;
+        v /= 91;
+    }
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode};
+
+    #[test]
+    fn test_base91() {
+        assert_eq!(decode(b"<*e7"), Some(20_427_156));
+        assert_eq!(encode(20_427_156, 4), "<*e7");
+        assert_eq!(decode(b"!!"), Some(0));
+        assert_eq!(encode(8280, 2), "{{");
+        assert_eq!(decode(b"a|"), None);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::ParseError;
+
+/// Bulletin addressee, `BLN` followed by a line number, letter or group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BulletinKind {
+    /// `BLN0` to `BLN9`, general bulletin lines.
+    Numbered(u8),
+    /// `BLNA` to `BLNZ`, announcements.
+    Announcement(char),
+    /// `BLN4WX`, line of a named bulletin group of up to 5 characters.
+    Group { line: u8, name: String },
+}
+
+impl FromStr for BulletinKind {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidBulletin(s.to_string());
+        let rest = s.strip_prefix("BLN").ok_or_else(err)?;
+        let mut chars = rest.chars();
+        let c = chars.next().ok_or_else(err)?;
+        let name = chars.as_str();
+        match (c, name) {
+            ('0'..='9', "") => Ok(BulletinKind::Numbered(c as u8 - b'0')),
+            ('A'..='Z', "") => Ok(BulletinKind::Announcement(c)),
+            ('0'..='9', _) if name.len() <= 5 && name.bytes().all(|c| c.is_ascii_alphanumeric()) =>
+                Ok(BulletinKind::Group { line: c as u8 - b'0', name: name.to_string() }),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Display for BulletinKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            BulletinKind::Numbered(v) => write!(f, "BLN{}", v),
+            BulletinKind::Announcement(v) => write!(f, "BLN{}", v),
+            BulletinKind::Group { line, name } => write!(f, "BLN{}{}", line, name),
+        }
+    }
+}
+
+/// Bulletin or announcement, a message addressed to `BLN*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bulletin {
+    pub kind: BulletinKind,
+    pub text: String,
+}
+
+impl FromStr for Bulletin {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidBulletin(s.to_string());
+        if !s.starts_with(':') || s.as_bytes().get(10) != Some(&b':') {
+            return Err(err());
+        }
+        let kind = s.get(1..10).ok_or_else(err)?.trim_end().parse().map_err(|_| err())?;
+        Ok(Bulletin {
+            kind,
+            text: s[11..].trim_end_matches(['\r', '\n']).to_string(),
+        })
+    }
+}
+
+impl Display for Bulletin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, ":{:<9}:{}", self.kind.to_string(), self.text)
+    }
+}
+
+/// Bulletin held by a [`BulletinBoard`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulletinEntry {
+    pub source: String,
+    pub bulletin: Bulletin,
+    pub received: Instant,
+}
+
+/// Latest version of each bulletin line per sender.
+#[derive(Debug, Clone)]
+pub struct BulletinBoard {
+    max_age: Duration,
+    entries: HashMap<(String, BulletinKind), BulletinEntry>,
+}
+
+impl BulletinBoard {
+    /// Board keeping entries not updated for longer than `max_age` until the next [`expire`](Self::expire).
+    pub fn new(max_age: Duration) -> Self {
+        BulletinBoard { max_age, entries: HashMap::new() }
+    }
+
+    /// Stores `bulletin`, replacing the same line previously sent by `source`.
+    pub fn insert(&mut self, source: &str, bulletin: Bulletin, now: Instant) {
+        let key = (source.to_string(), bulletin.kind.clone());
+        self.entries.insert(key, BulletinEntry { source: source.to_string(), bulletin, received: now });
+    }
+
+    /// Removes entries older than the maximum age.
+    pub fn expire(&mut self, now: Instant) {
+        let max_age = self.max_age;
+        self.entries.retain(|_, v| now.saturating_duration_since(v.received) <= max_age);
+    }
+
+    pub fn get(&self, source: &str, kind: &BulletinKind) -> Option<&BulletinEntry> {
+        self.entries.get(&(source.to_string(), kind.clone()))
+    }
+
+    /// Entries ordered by kind, then sender, the way clients list them.
+    pub fn entries(&self) -> Vec<&BulletinEntry> {
+        let mut v: Vec<_> = self.entries.values().collect();
+        v.sort_by(|a, b| (&a.bulletin.kind, &a.source).cmp(&(&b.bulletin.kind, &b.source)));
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{Bulletin, BulletinBoard, BulletinKind, ParseError};
+
+    #[test]
+    fn test_parse() {
+        let v: Bulletin = ":BLN3     :Snow expected in Tampa RSN".parse().unwrap();
+        assert_eq!(v.kind, BulletinKind::Numbered(3));
+        assert_eq!(v.text, "Snow expected in Tampa RSN");
+
+        let v: Bulletin = ":BLNQ     :Mt St Helen digi will be QRT this weekend".parse().unwrap();
+        assert_eq!(v.kind, BulletinKind::Announcement('Q'));
+
+        let v: Bulletin = ":BLN4WX   :Severe storm warning".parse().unwrap();
+        assert_eq!(v.kind, BulletinKind::Group { line: 4, name: "WX".to_string() });
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [":BLN      :Text", ":BLNAB    :Text", ":BLN1TOOLONG:Text", ":WU2Z     :Text", ":BLN1"] {
+            assert_eq!(s.parse::<Bulletin>(), Err(ParseError::InvalidBulletin(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [":BLN3     :Snow expected", ":BLNQ     :QRT", ":BLN4WX   :Storm", ":BLN0ARES :"] {
+            assert_eq!(s.parse::<Bulletin>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_board() {
+        let start = Instant::now();
+        let mut board = BulletinBoard::new(Duration::from_secs(3600));
+        board.insert("WU2Z", ":BLN1     :Net at 8pm".parse().unwrap(), start);
+        board.insert("N0CALL", ":BLN1     :Road closed".parse().unwrap(), start);
+        board.insert("WU2Z", ":BLNA     :Hamfest Saturday".parse().unwrap(), start);
+        board.insert("WU2Z", ":BLN1     :Net at 9pm".parse().unwrap(), start + Duration::from_secs(1800));
+        assert_eq!(board.len(), 3);
+        assert_eq!(board.get("WU2Z", &BulletinKind::Numbered(1)).unwrap().bulletin.text, "Net at 9pm");
+
+        let order: Vec<_> = board.entries().iter().map(|v| (v.source.as_str(), v.bulletin.text.as_str())).collect();
+        assert_eq!(order, [("N0CALL", "Road closed"), ("WU2Z", "Net at 9pm"), ("WU2Z", "Hamfest Saturday")]);
+
+        board.expire(start + Duration::from_secs(3601));
+        assert_eq!(board.len(), 1);
+        assert!(board.get("N0CALL", &BulletinKind::Numbered(1)).is_none());
+        board.expire(start + Duration::from_secs(5401));
+        assert!(board.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{Feet, Hertz, Megahertz, Position, Telemetry, Weather};
+use crate::telemetry;
+
+/// Datum and extra position precision, `!DAO!`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dao {
+    /// Datum letter, upper case for human readable precision, lower case for base-91.
+    pub datum: char,
+    /// Extra minutes of latitude beyond the hundredths sent in the position.
+    pub latitude: Option<f32>,
+    /// Extra minutes of longitude.
+    pub longitude: Option<f32>,
+}
+
+// Resolution of the extra digits
+const HUMAN_MINUTES: f32 = 0.001;
+const BASE91_MINUTES: f32 = 0.01 / 91.0;
+
+impl Dao {
+    fn parse(b: &[u8]) -> Option<Dao> {
+        let datum = b[0] as char;
+        let offset = |c: u8| match datum {
+            'W' if c.is_ascii_digit() => Some(Some((c - b'0') as f32 * HUMAN_MINUTES)),
+            'w' if (b'!'..=b'{').contains(&c) => Some(Some((c - b'!') as f32 * BASE91_MINUTES)),
+            _ if c == b' ' => Some(None),
+            // Other datums carry no extra precision
+            'W' | 'w' => None,
+            _ => Some(None),
+        };
+        if !datum.is_ascii_alphabetic() {
+            return None;
+        }
+        Some(Dao { datum, latitude: offset(b[1])?, longitude: offset(b[2])? })
+    }
+
+    /// `position` with the extra precision added away from the equator and prime meridian.
+    pub fn apply(&self, position: Position) -> Position {
+        let mut v = position;
+        if let Some(m) = self.latitude {
+            v.latitude += v.latitude.signum() * m / 60.0;
+        }
+        if let Some(m) = self.longitude {
+            v.longitude += v.longitude.signum() * m / 60.0;
+        }
+        v
+    }
+
+    fn offset_char(&self, v: Option<f32>) -> char {
+        match (self.datum, v) {
+            ('W', Some(m)) => (b'0' + (m / HUMAN_MINUTES).round().clamp(0.0, 9.0) as u8) as char,
+            ('w', Some(m)) => (b'!' + (m / BASE91_MINUTES).round().clamp(0.0, 90.0) as u8) as char,
+            _ => ' ',
+        }
+    }
+}
+
+impl Display for Dao {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "!{}{}{}!", self.datum, self.offset_char(self.latitude), self.offset_char(self.longitude))
+    }
+}
+
+/// Tone of a frequency specification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tone {
+    /// `Toff`, no tone.
+    Off,
+    /// `Tnnn`, tone sent on transmit only.
+    Tone(Hertz),
+    /// `Cnnn`, tone squelch on transmit and receive.
+    Ctcss(Hertz),
+    /// `Dnnn`, digital coded squelch.
+    Dcs(u16),
+    /// `1750`, tone burst.
+    Burst,
+}
+
+impl Tone {
+    fn parse(s: &str) -> Option<Tone> {
+        let number = || s.get(1..).filter(|v| v.len() == 3 && v.bytes().all(|c| c.is_ascii_digit()))?.parse().ok();
+        match s.as_bytes().first()? {
+            _ if s.eq_ignore_ascii_case("Toff") => Some(Tone::Off),
+            _ if s == "1750" => Some(Tone::Burst),
+            b'T' => Some(Tone::Tone(Hertz(number()? as f32))),
+            b'C' => Some(Tone::Ctcss(Hertz(number()? as f32))),
+            b'D' => Some(Tone::Dcs(number()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Tone {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Tone::Off => write!(f, "Toff"),
+            Tone::Tone(v) => write!(f, "T{:03}", v.0.round() as u32),
+            Tone::Ctcss(v) => write!(f, "C{:03}", v.0.round() as u32),
+            Tone::Dcs(v) => write!(f, "D{:03}", v),
+            Tone::Burst => write!(f, "1750"),
+        }
+    }
+}
+
+// `+nnn` or `-nnn` repeater offset in 10 kHz steps
+fn parse_offset(s: &str) -> Option<Megahertz> {
+    let sign = match s.as_bytes().first()? {
+        b'+' => 1.0,
+        b'-' => -1.0,
+        _ => return None,
+    };
+    let digits = s.get(1..).filter(|v| v.len() == 3 && v.bytes().all(|c| c.is_ascii_digit()))?;
+    Some(Megahertz(sign * digits.parse::<f32>().ok()? / 100.0))
+}
+
+/// Data found in a free text comment, and the text left for humans.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Comment {
+    /// `/A=aaaaaa`, altitude in feet.
+    pub altitude: Option<Feet>,
+    pub dao: Option<Dao>,
+    /// Base-91 `|ss11...|` telemetry.
+    pub telemetry: Option<Telemetry>,
+    /// `FFF.FFFMHz` voice frequency, with the tone and offset following it.
+    pub frequency: Option<Megahertz>,
+    pub tone: Option<Tone>,
+    pub offset: Option<Megahertz>,
+    /// Weather of weather station reports, such as `t077h50`. [`Comment::parse`]
+    /// leaves these in the text, [`crate::Packet::parsed_comment`] fills them in.
+    pub weather: Option<Weather>,
+    pub text: String,
+}
+
+// Removes `len` bytes at `start`, with one of the spaces around them
+fn remove(s: &str, start: usize, len: usize) -> String {
+    let (head, mut tail) = (&s[..start], &s[start + len..]);
+    if head.is_empty() || head.ends_with(' ') {
+        tail = tail.strip_prefix(' ').unwrap_or(tail);
+    }
+    format!("{}{}", head, tail)
+}
+
+fn find_dao(s: &str) -> Option<(usize, Dao)> {
+    let b = s.as_bytes();
+    (0..b.len().saturating_sub(4))
+        .filter(|&i| b[i] == b'!' && b[i + 4] == b'!' && b[i..i + 5].is_ascii())
+        .find_map(|i| Some((i, Dao::parse(&b[i + 1..i + 4])?)))
+}
+
+fn find_altitude(s: &str) -> Option<(usize, Feet)> {
+    s.match_indices("/A=").find_map(|(i, _)| {
+        let v = s.get(i + 3..i + 9)?;
+        if !v.bytes().all(|c| c.is_ascii_digit() || c == b'-') {
+            return None;
+        }
+        Some((i, Feet(v.parse::<i32>().ok()? as f32)))
+    })
+}
+
+// `FFF.FFFMHz` and the tone and offset tokens after it, returns the start
+// and length of the whole specification
+fn find_frequency(s: &str, comment: &mut Comment) -> Option<(usize, usize)> {
+    let b = s.as_bytes();
+    let start = s.match_indices("MHz").map(|(i, _)| i).find_map(|i| {
+        let start = i.checked_sub(7)?;
+        let digits = &b[start..i];
+        let valid = digits.iter().enumerate().all(|(j, c)| if j == 3 { *c == b'.' } else { c.is_ascii_digit() });
+        if valid { Some(start) } else { None }
+    })?;
+    comment.frequency = Some(Megahertz(s[start..start + 7].parse().ok()?));
+    let mut end = start + 10;
+    while let Some(rest) = s[end..].strip_prefix(' ') {
+        let token = rest.split(' ').next().unwrap_or("");
+        if let Some(v) = Tone::parse(token).filter(|_| comment.tone.is_none()) {
+            comment.tone = Some(v);
+        } else if let Some(v) = parse_offset(token).filter(|_| comment.offset.is_none()) {
+            comment.offset = Some(v);
+        } else {
+            break;
+        }
+        end += 1 + token.len();
+    }
+    Some((start, end - start))
+}
+
+impl Comment {
+    /// Extracts everything this crate understands from a comment, except
+    /// weather which only weather stations send.
+    pub fn parse(s: &str) -> Comment {
+        let mut comment = Comment::default();
+        let mut text = s.to_string();
+        if let Some((v, rest)) = telemetry::split_comment(&text) {
+            comment.telemetry = Some(v);
+            text = rest;
+        }
+        if let Some((i, v)) = find_dao(&text) {
+            comment.dao = Some(v);
+            text = remove(&text, i, 5);
+        }
+        if let Some((i, v)) = find_altitude(&text) {
+            comment.altitude = Some(v);
+            text = remove(&text, i, 9);
+        }
+        if let Some((i, len)) = find_frequency(&text, &mut comment) {
+            text = remove(&text, i, len);
+        }
+        comment.text = text.trim().to_string();
+        comment
+    }
+}
+
+impl Display for Comment {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Fragments are written in a fixed order, separated by spaces
+        let mut separator = "";
+        if let Some(v) = self.frequency {
+            write!(f, "{:07.3}MHz", v.0)?;
+            if let Some(v) = self.tone {
+                write!(f, " {}", v)?;
+            }
+            if let Some(v) = self.offset {
+                write!(f, " {}{:03}", if v.0 < 0.0 { '-' } else { '+' }, (v.0.abs() * 100.0).round() as u32)?;
+            }
+            separator = " ";
+        }
+        if let Some(v) = &self.weather {
+            write!(f, "{}", separator)?;
+            v.fmt_known_fields(f)?;
+            separator = " ";
+        }
+        if !self.text.is_empty() {
+            write!(f, "{}{}", separator, self.text)?;
+            separator = " ";
+        }
+        if let Some(v) = self.altitude {
+            write!(f, "{}/A={:06}", separator, v.0.round() as i32)?;
+        }
+        if let Some(v) = self.telemetry.as_ref().and_then(Telemetry::encode_comment) {
+            write!(f, "{}", v)?;
+        }
+        if let Some(v) = self.dao {
+            write!(f, "{}", v)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Comment, Dao, Fahrenheits, Feet, Hertz, Megahertz, Percent, Position, TelemetrySequence, Tone,
+        Weather};
+
+    #[test]
+    fn test_altitude_and_dao() {
+        let v = Comment::parse("Home /A=001234 QTH!W52!");
+        assert_eq!(v.altitude, Some(Feet(1234.0)));
+        let dao = v.dao.unwrap();
+        assert_eq!(dao.datum, 'W');
+        assert_abs_diff_eq!(dao.latitude.unwrap(), 0.005, epsilon = 0.000001);
+        assert_abs_diff_eq!(dao.longitude.unwrap(), 0.002, epsilon = 0.000001);
+        assert_eq!(v.text, "Home QTH");
+
+        assert_eq!(Comment::parse("/A=-00012").altitude, Some(Feet(-12.0)));
+        assert_eq!(Comment::parse("/A=12").altitude, None);
+
+        let dao = Comment::parse("!wZZ!").dao.unwrap();
+        assert_abs_diff_eq!(dao.latitude.unwrap(), 57.0 * 0.01 / 91.0, epsilon = 0.000001);
+        let p = dao.apply(Position::from_latlng(-49.0, -72.0));
+        assert!(p.latitude < -49.0 && p.longitude < -72.0);
+        assert_eq!(Comment::parse("Wow!W!a!").dao, None);
+        assert_eq!(Comment::parse("!I  !").dao, Some(Dao { datum: 'I', latitude: None, longitude: None }));
+    }
+
+    #[test]
+    fn test_frequency() {
+        let v = Comment::parse("146.520MHz T100 -060 Net at 8pm");
+        assert_eq!(v.frequency, Some(Megahertz(146.52)));
+        assert_eq!(v.tone, Some(Tone::Tone(Hertz(100.0))));
+        assert_eq!(v.offset, Some(Megahertz(-0.6)));
+        assert_eq!(v.text, "Net at 8pm");
+
+        assert_eq!(Comment::parse("444.925MHz C131 +500").tone, Some(Tone::Ctcss(Hertz(131.0))));
+        assert_eq!(Comment::parse("145.500MHz Toff").tone, Some(Tone::Off));
+        assert_eq!(Comment::parse("QRV 145.500MHz D023").tone, Some(Tone::Dcs(23)));
+        assert_eq!(Comment::parse("145.5MHz").frequency, None);
+    }
+
+    #[test]
+    fn test_telemetry() {
+        let v = Comment::parse("Garden |ss11|");
+        assert_eq!(v.telemetry.unwrap().sequence, TelemetrySequence::Number(7544));
+        assert_eq!(v.text, "Garden");
+        assert_eq!(Comment::parse("").text, "");
+
+        // Text that looks like weather fields stays
+        for s in ["P123 Mobile", "r100 km", "t077h50 Garden"] {
+            let v = Comment::parse(s);
+            assert_eq!((v.weather, v.text.as_str()), (None, s));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["146.520MHz T100 -060 Net", "Home /A=001234!W52!", "Balloon|ss11|", "Plain text"] {
+            assert_eq!(Comment::parse(s).to_string(), s);
+        }
+
+        let weather = Weather { temperature: Some(Fahrenheits(77.0)), humidity: Some(Percent(50.0)), ..Weather::default() };
+        let v = Comment { weather: Some(weather), text: "Garden".to_string(), ..Comment::default() };
+        assert_eq!(v.to_string(), "t077h50 Garden");
+    }
+}
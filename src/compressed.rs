@@ -0,0 +1,277 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{base91, Degrees, Feet, Knots, Miles, ParseError, Position};
+
+/// Data carried in the `cs` bytes of a compressed position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompressedExtension {
+    CourseSpeed(Degrees, Knots),
+    RadioRange(Miles),
+    Altitude(Feet),
+}
+
+/// Source of the NMEA sentence a compressed position was built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmeaSource {
+    Other,
+    Gll,
+    Gga,
+    Rmc,
+}
+
+/// Software or device that compressed the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionOrigin {
+    Compressed,
+    TncBText,
+    Software,
+    Tbd,
+    Kpc3,
+    Pico,
+    OtherTracker,
+    Digipeater,
+}
+
+/// Compression type (`T`) byte of a compressed position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressionType {
+    pub gps_fix_current: bool,
+    pub nmea_source: NmeaSource,
+    pub origin: CompressionOrigin,
+}
+
+impl CompressionType {
+    fn from_byte(v: u8) -> Option<CompressionType> {
+        let bits = v.checked_sub(b'!').filter(|&v| v < 64)?;
+        let nmea_source = match (bits >> 3) & 0x03 {
+            0 => NmeaSource::Other,
+            1 => NmeaSource::Gll,
+            2 => NmeaSource::Gga,
+            _ => NmeaSource::Rmc,
+        };
+        let origin = match bits & 0x07 {
+            0 => CompressionOrigin::Compressed,
+            1 => CompressionOrigin::TncBText,
+            2 => CompressionOrigin::Software,
+            3 => CompressionOrigin::Tbd,
+            4 => CompressionOrigin::Kpc3,
+            5 => CompressionOrigin::Pico,
+            6 => CompressionOrigin::OtherTracker,
+            _ => CompressionOrigin::Digipeater,
+        };
+        Some(CompressionType { gps_fix_current: bits & 0x20 != 0, nmea_source, origin })
+    }
+
+    fn to_byte(self) -> u8 {
+        let fix = if self.gps_fix_current { 0x20 } else { 0 };
+        b'!' + fix + ((self.nmea_source as u8) << 3) + self.origin as u8
+    }
+}
+
+/// 13-byte compressed position block: `/YYYYXXXX$csT`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressedPosition {
+    pub position: Position,
+    pub symbol_table: u8,
+    pub symbol_code: u8,
+    pub extension: Option<CompressedExtension>,
+    /// `None` when the `cs` bytes are unused, in which case `T` is ignored.
+    /// When encoding, the NMEA source is adjusted so `T` matches the extension.
+    pub compression_type: Option<CompressionType>,
+}
+
+pub(crate) const COMPRESSED_LEN: usize = 13;
+
+impl FromStr for CompressedPosition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidCompressedPosition(s.to_string());
+        let b = s.as_bytes();
+        if b.len() != COMPRESSED_LEN {
+            return Err(err());
+        }
+        let symbol_table = b[0];
+        if !(symbol_table == b'/' || symbol_table == b'\\'
+            || symbol_table.is_ascii_uppercase() || (b'a'..=b'j').contains(&symbol_table)) {
+            return Err(err());
+        }
+        let y = base91::decode(&b[1..5]).ok_or_else(err)?;
+        let x = base91::decode(&b[5..9]).ok_or_else(err)?;
+        let latitude = 90.0 - y as f64 / 380_926.0;
+        let longitude = -180.0 + x as f64 / 190_463.0;
+        if latitude < -90.0 || longitude > 180.0 {
+            return Err(err());
+        }
+
+        let (c, s, t) = (b[10], b[11], b[12]);
+        let (extension, compression_type) = if c == b' ' {
+            (None, None)
+        } else {
+            let compression_type = CompressionType::from_byte(t).ok_or_else(err)?;
+            let cs = base91::decode(&[c, s]).ok_or_else(err)?;
+            let extension = if compression_type.nmea_source == NmeaSource::Gga {
+                CompressedExtension::Altitude(Feet(1.002f32.powi(cs as i32)))
+            } else if c == b'{' {
+                CompressedExtension::RadioRange(Miles(2.0 * 1.08f32.powi((s - b'!') as i32)))
+            } else {
+                let course = (c - b'!') as f32 * 4.0;
+                let speed = 1.08f32.powi((s - b'!') as i32) - 1.0;
+                CompressedExtension::CourseSpeed(Degrees(course), Knots(speed))
+            };
+            (Some(extension), Some(compression_type))
+        };
+
+        Ok(CompressedPosition {
+            position: Position::from_latlng(latitude as f32, longitude as f32),
+            symbol_table,
+            symbol_code: b[9],
+            extension,
+            compression_type,
+        })
+    }
+}
+
+impl Display for CompressedPosition {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let y = (380_926.0 * (90.0 - self.position.latitude as f64)) as u32;
+        let x = (190_463.0 * (180.0 + self.position.longitude as f64)) as u32;
+        write!(f, "{}{}{}{}", self.symbol_table as char, base91::encode(y, 4), base91::encode(x, 4),
+            self.symbol_code as char)?;
+
+        // Altitude is only read from `cs` when the source is GGA, and other
+        // extensions only when it isn't
+        let mut compression_type = self.compression_type.unwrap_or(CompressionType {
+            gps_fix_current: false,
+            nmea_source: NmeaSource::Other,
+            origin: CompressionOrigin::Compressed,
+        });
+        match self.extension {
+            Some(CompressedExtension::Altitude(_)) => compression_type.nmea_source = NmeaSource::Gga,
+            Some(_) if compression_type.nmea_source == NmeaSource::Gga =>
+                compression_type.nmea_source = NmeaSource::Other,
+            _ => (),
+        }
+        let cs = match self.extension {
+            Some(CompressedExtension::Altitude(v)) =>
+                base91::encode((v.0.max(1.0).ln() / 1.002f32.ln()).round() as u32, 2),
+            Some(CompressedExtension::RadioRange(v)) =>
+                format!("{{{}", base91::encode(((v.0 / 2.0).max(1.0).ln() / 1.08f32.ln()).round() as u32, 1)),
+            Some(CompressedExtension::CourseSpeed(course, speed)) => {
+                let c = (course.0.rem_euclid(360.0) / 4.0).round() as u32 % 90;
+                let s = ((speed.0.max(0.0) + 1.0).ln() / 1.08f32.ln()).round() as u32;
+                format!("{}{}", base91::encode(c, 1), base91::encode(s.min(90), 1))
+            }
+            None => return write!(f, " sT"),
+        };
+        write!(f, "{}{}", cs, compression_type.to_byte() as char)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CompressedExtension, CompressedPosition, CompressionOrigin, CompressionType,
+        Degrees, Feet, Knots, Miles, NmeaSource, ParseError, Position};
+
+    #[test]
+    fn test_decode_course_speed() {
+        let p: CompressedPosition = "/5L!!<*e7>7P[".parse().unwrap();
+        assert_abs_diff_eq!(p.position.latitude, 49.5, epsilon = 0.0001);
+        assert_abs_diff_eq!(p.position.longitude, -72.75, epsilon = 0.0001);
+        assert_eq!(p.symbol_code, b'>');
+        match p.extension {
+            Some(CompressedExtension::CourseSpeed(Degrees(c), Knots(s))) => {
+                assert_eq!(c, 88.0);
+                assert_abs_diff_eq!(s, 36.2, epsilon = 0.1);
+            }
+            v => panic!("unexpected {:?}", v),
+        }
+        assert_eq!(p.compression_type, Some(CompressionType {
+            gps_fix_current: true,
+            nmea_source: NmeaSource::Rmc,
+            origin: CompressionOrigin::Software,
+        }));
+    }
+
+    #[test]
+    fn test_decode_altitude_and_range() {
+        let p: CompressedPosition = "/5L!!<*e7OS]S".parse().unwrap();
+        match p.extension {
+            Some(CompressedExtension::Altitude(v)) => assert_abs_diff_eq!(v.0, 10004.0, epsilon = 1.0),
+            v => panic!("unexpected {:?}", v),
+        }
+        let p: CompressedPosition = "/5L!!<*e7>{?!".parse().unwrap();
+        match p.extension {
+            Some(CompressedExtension::RadioRange(Miles(v))) => assert_abs_diff_eq!(v, 20.13, epsilon = 0.01),
+            v => panic!("unexpected {:?}", v),
+        }
+        let p: CompressedPosition = "/5L!!<*e7> sT".parse().unwrap();
+        assert_eq!(p.extension, None);
+        assert_eq!(p.compression_type, None);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        for s in ["/5L!!<*e7>7P", "x5L!!<*e7>7P[", "/5L!~<*e7>7P["] {
+            assert_eq!(s.parse::<CompressedPosition>(), Err(ParseError::InvalidCompressedPosition(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut p = CompressedPosition {
+            position: Position::from_latlng(49.5, -72.75),
+            symbol_table: b'/',
+            symbol_code: b'>',
+            extension: Some(CompressedExtension::CourseSpeed(Degrees(88.0), Knots(36.2))),
+            compression_type: "/5L!!<*e7>7P[".parse::<CompressedPosition>().unwrap().compression_type,
+        };
+        assert_eq!(p.to_string(), "/5L!!<*e7>7P[");
+        p.extension = Some(CompressedExtension::RadioRange(Miles(20.13)));
+        assert_eq!(p.to_string(), "/5L!!<*e7>{?[");
+        p.compression_type = None;
+        assert_eq!(p.to_string(), "/5L!!<*e7>{?!");
+        p.extension = None;
+        assert_eq!(p.to_string(), "/5L!!<*e7> sT");
+    }
+
+    #[test]
+    fn test_encode_type_from_extension() {
+        let mut p = CompressedPosition {
+            position: Position::from_latlng(49.5, -72.75),
+            symbol_table: b'/',
+            symbol_code: b'>',
+            extension: Some(CompressedExtension::CourseSpeed(Degrees(88.0), Knots(36.2))),
+            compression_type: None,
+        };
+        let q: CompressedPosition = p.to_string().parse().unwrap();
+        assert_eq!(p.to_string(), "/5L!!<*e7>7P!");
+        assert!(matches!(q.extension, Some(CompressedExtension::CourseSpeed(Degrees(c), _)) if c == 88.0));
+
+        // Altitude needs a GGA source, course and speed can't have one
+        p.extension = Some(CompressedExtension::Altitude(Feet(10004.0)));
+        p.compression_type = "/5L!!<*e7>7P[".parse::<CompressedPosition>().unwrap().compression_type;
+        let q: CompressedPosition = p.to_string().parse().unwrap();
+        assert!(matches!(q.extension, Some(CompressedExtension::Altitude(Feet(v))) if (v - 10004.0).abs() < 20.0));
+        assert_eq!(q.compression_type.unwrap().origin, CompressionOrigin::Software);
+
+        p.extension = Some(CompressedExtension::RadioRange(Miles(20.13)));
+        p.compression_type = q.compression_type;
+        let q: CompressedPosition = p.to_string().parse().unwrap();
+        assert!(matches!(q.extension, Some(CompressedExtension::RadioRange(_))));
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        // Coordinates are kept as `f32`, so they may be off by one base-91 unit
+        for s in ["/5L!!<*e7>7P[", "/5L!!<*e7OS]S", "/5L!!<*e7>{?!", "\\5L!!<*e7> sT", "a5L!!<*e7>7P["] {
+            let p = s.parse::<CompressedPosition>().unwrap();
+            let q = p.to_string().parse::<CompressedPosition>().unwrap();
+            assert_abs_diff_eq!(p.position.latitude, q.position.latitude, epsilon = 0.00001);
+            assert_abs_diff_eq!(p.position.longitude, q.position.longitude, epsilon = 0.00001);
+            assert_eq!(p.to_string()[9..], s[9..]);
+            assert_eq!(p.symbol_table, q.symbol_table);
+        }
+    }
+}
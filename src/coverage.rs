@@ -0,0 +1,117 @@
+use crate::{DataExtension, Degrees, Feet, Miles, Position, Watts};
+
+// Mean radius of the Earth
+const EARTH_RADIUS_MILES: f64 = 3958.8;
+
+// Width of the sector drawn for directional antennas
+const SECTOR_WIDTH: Degrees = Degrees(90.0);
+
+// APRS101 range estimate, `sqrt(2 * height * sqrt(power / 10 * gain / 2))`
+// with the gain as a power ratio
+pub(crate) fn phg_range(power: Watts, height: Feet, gain: f32) -> Miles {
+    let ratio = 10f32.powf(gain / 10.0);
+    Miles((2.0 * height.0 * (power.0 / 10.0 * ratio / 2.0).sqrt()).sqrt())
+}
+
+/// Area a station claims to cover, from its `PHG` or `RNG` extension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coverage {
+    Circle { center: Position, radius: Miles },
+    /// Directional antenna: `width` degrees centred on `direction`.
+    Sector { center: Position, radius: Miles, direction: Degrees, width: Degrees },
+}
+
+impl Coverage {
+    /// Coverage of a station at `center`, `None` for extensions without a range.
+    pub fn from_extension(center: Position, extension: &DataExtension) -> Option<Coverage> {
+        let radius = extension.range()?;
+        match *extension {
+            DataExtension::PowerHeightGain { directivity: Some(direction), .. } =>
+                Some(Coverage::Sector { center, radius, direction, width: SECTOR_WIDTH }),
+            _ => Some(Coverage::Circle { center, radius }),
+        }
+    }
+
+    pub fn radius(&self) -> Miles {
+        match *self {
+            Coverage::Circle { radius, .. } | Coverage::Sector { radius, .. } => radius,
+        }
+    }
+
+    /// Outline with `points` vertices on the edge, plus the centre for sectors.
+    pub fn polygon(&self, points: usize) -> Vec<Position> {
+        let points = points.max(3);
+        match *self {
+            Coverage::Circle { center, radius } => (0..points)
+                .map(|i| destination(center, i as f64 * 360.0 / points as f64, radius))
+                .collect(),
+            Coverage::Sector { center, radius, direction, width } => {
+                let start = direction.0 as f64 - width.0 as f64 / 2.0;
+                let step = width.0 as f64 / (points - 1) as f64;
+                std::iter::once(center)
+                    .chain((0..points).map(|i| destination(center, start + i as f64 * step, radius)))
+                    .collect()
+            }
+        }
+    }
+}
+
+// Great circle destination from `center` along `bearing` degrees
+fn destination(center: Position, bearing: f64, distance: Miles) -> Position {
+    let (lat, lng) = ((center.latitude as f64).to_radians(), (center.longitude as f64).to_radians());
+    let bearing = bearing.to_radians();
+    let d = distance.0 as f64 / EARTH_RADIUS_MILES;
+    let lat2 = (lat.sin() * d.cos() + lat.cos() * d.sin() * bearing.cos()).asin();
+    let lng2 = lng + (bearing.sin() * d.sin() * lat.cos()).atan2(d.cos() - lat.sin() * lat2.sin());
+    let lng2 = (lng2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0;
+    Position::from_latlng(lat2.to_degrees() as f32, lng2 as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::phg_range;
+    use crate::{Coverage, DataExtension, Degrees, Feet, Miles, Position, Watts};
+
+    #[test]
+    fn test_phg_range() {
+        assert_abs_diff_eq!(phg_range(Watts(25.0), Feet(20.0), 3.0).0, 7.95, epsilon = 0.01);
+        // 10 W, 0 dB: sqrt(2h * sqrt(0.5))
+        assert_abs_diff_eq!(phg_range(Watts(10.0), Feet(320.0), 0.0).0, 21.27, epsilon = 0.01);
+        assert_eq!(phg_range(Watts(0.0), Feet(320.0), 6.0), Miles(0.0));
+
+        let v: DataExtension = "PHG5132".parse().unwrap();
+        assert_abs_diff_eq!(v.range().unwrap().0, 7.95, epsilon = 0.01);
+        assert_eq!("RNG0050".parse::<DataExtension>().unwrap().range(), Some(Miles(50.0)));
+        assert_eq!("088/036".parse::<DataExtension>().unwrap().range(), None);
+    }
+
+    #[test]
+    fn test_circle() {
+        let center = Position::from_latlng(0.0, 0.0);
+        let v = Coverage::from_extension(center, &"RNG0069".parse().unwrap()).unwrap();
+        assert_eq!(v, Coverage::Circle { center, radius: Miles(69.0) });
+        let outline = v.polygon(4);
+        assert_eq!(outline.len(), 4);
+        // 69 miles is about one degree at the equator
+        assert_abs_diff_eq!(outline[0].latitude, 0.9986, epsilon = 0.001);
+        assert_abs_diff_eq!(outline[1].longitude, 0.9986, epsilon = 0.001);
+        assert_abs_diff_eq!(outline[2].latitude, -0.9986, epsilon = 0.001);
+    }
+
+    #[test]
+    fn test_sector() {
+        let center = Position::from_latlng(0.0, 179.5);
+        let v = Coverage::from_extension(center, &"PHG5132".parse().unwrap()).unwrap();
+        let Coverage::Sector { direction, width, .. } = v else { panic!("not a sector") };
+        assert_eq!(direction, Degrees(90.0));
+        assert_eq!(width, Degrees(90.0));
+
+        let v = Coverage::Sector { center, radius: Miles(69.0), direction: Degrees(90.0), width: Degrees(90.0) };
+        let outline = v.polygon(3);
+        assert_eq!(outline.len(), 4);
+        assert_eq!(outline[0], center);
+        // Due east crosses the antimeridian
+        assert_abs_diff_eq!(outline[2].longitude, -179.5014, epsilon = 0.001);
+        assert!(outline[1].latitude > 0.0 && outline[3].latitude < 0.0);
+    }
+}
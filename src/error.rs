@@ -0,0 +1,62 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Reason a packet could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    MissingHeader,
+    InvalidSource(String),
+    InvalidDestination(String),
+    InvalidPath(String),
+    EmptyInformationField,
+    InvalidTimestamp(String),
+    InvalidPosition(String),
+    InvalidCompressedPosition(String),
+    InvalidMicE(String),
+    InvalidObject(String),
+    InvalidItem(String),
+    InvalidMessage(String),
+    InvalidBulletin(String),
+    InvalidWeather(String),
+    InvalidPeetBros(String),
+    InvalidTelemetry(String),
+    InvalidTelemetryMetadata(String),
+    InvalidStatus(String),
+    InvalidQuery(String),
+    InvalidCapabilities(String),
+    InvalidNmea(String),
+    InvalidThirdParty(String),
+    InvalidExtension(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParseError::MissingHeader => write!(f, "missing `SOURCE>DEST:` header"),
+            ParseError::InvalidSource(v) => write!(f, "invalid source callsign `{}`", v),
+            ParseError::InvalidDestination(v) => write!(f, "invalid destination `{}`", v),
+            ParseError::InvalidPath(v) => write!(f, "invalid path element `{}`", v),
+            ParseError::EmptyInformationField => write!(f, "empty information field"),
+            ParseError::InvalidTimestamp(v) => write!(f, "invalid timestamp `{}`", v),
+            ParseError::InvalidPosition(v) => write!(f, "invalid position `{}`", v),
+            ParseError::InvalidCompressedPosition(v) => write!(f, "invalid compressed position `{}`", v),
+            ParseError::InvalidMicE(v) => write!(f, "invalid Mic-E data `{}`", v),
+            ParseError::InvalidObject(v) => write!(f, "invalid object `{}`", v),
+            ParseError::InvalidItem(v) => write!(f, "invalid item `{}`", v),
+            ParseError::InvalidMessage(v) => write!(f, "invalid message `{}`", v),
+            ParseError::InvalidBulletin(v) => write!(f, "invalid bulletin `{}`", v),
+            ParseError::InvalidWeather(v) => write!(f, "invalid weather report `{}`", v),
+            ParseError::InvalidPeetBros(v) => write!(f, "invalid Peet Bros weather report `{}`", v),
+            ParseError::InvalidTelemetry(v) => write!(f, "invalid telemetry `{}`", v),
+            ParseError::InvalidTelemetryMetadata(v) => write!(f, "invalid telemetry metadata `{}`", v),
+            ParseError::InvalidStatus(v) => write!(f, "invalid status `{}`", v),
+            ParseError::InvalidQuery(v) => write!(f, "invalid query `{}`", v),
+            ParseError::InvalidCapabilities(v) => write!(f, "invalid capabilities `{}`", v),
+            ParseError::InvalidNmea(v) => write!(f, "invalid NMEA sentence `{}`", v),
+            ParseError::InvalidThirdParty(v) => write!(f, "invalid third-party packet `{}`", v),
+            ParseError::InvalidExtension(v) => write!(f, "invalid data extension `{}`", v),
+        }
+    }
+}
+
+impl Error for ParseError {}
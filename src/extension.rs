@@ -0,0 +1,225 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Degrees, Feet, Knots, Miles, ParseError, Watts};
+use crate::coverage::phg_range;
+
+// Extensions are always 7 bytes
+pub(crate) const EXTENSION_LEN: usize = 7;
+
+/// Fixed-length data extension following an uncompressed position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataExtension {
+    /// `ccc/sss`, `None` for `...` or blank fields. A course of 0 means unknown.
+    CourseSpeed { course: Option<Degrees>, speed: Option<Knots> },
+    /// `PHGphgd`, station power, antenna height above average terrain, gain and directivity.
+    PowerHeightGain { power: Watts, height: Feet, gain: f32, directivity: Option<Degrees> },
+    /// `RNGrrrr`, omni-directional radio range.
+    Range(Miles),
+    /// `DFSshgd`, direction finding signal strength in S-points with antenna height, gain and directivity.
+    SignalStrength { strength: u8, height: Feet, gain: f32, directivity: Option<Degrees> },
+    /// `Tyy/Cxx` of area objects: shape, color and offsets of the far corner in degrees.
+    Area { shape: u8, color: u8, lat_offset: f32, lng_offset: f32 },
+}
+
+fn digit(c: u8) -> Option<u8> {
+    if c.is_ascii_digit() { Some(c - b'0') } else { None }
+}
+
+fn number(b: &[u8]) -> Option<u32> {
+    b.iter().try_fold(0, |acc, &c| Some(acc * 10 + digit(c)? as u32))
+}
+
+// Height is 10 * 2^h feet, `h` may go past `9` into the rest of ASCII
+fn height(c: u8) -> Option<Feet> {
+    let h = c.checked_sub(b'0').filter(|&v| v < 30)?;
+    Some(Feet(10.0 * (1u32 << h) as f32))
+}
+
+fn height_code(v: Feet) -> char {
+    let h = (v.0 / 10.0).max(1.0).log2().round() as u8;
+    (b'0' + h.min(29)) as char
+}
+
+// Directivity 0 is omni, 1 to 8 are multiples of 45 degrees
+fn directivity(c: u8) -> Option<Option<Degrees>> {
+    match digit(c)? {
+        0 => Some(None),
+        d @ 1..=8 => Some(Some(Degrees(d as f32 * 45.0))),
+        _ => None,
+    }
+}
+
+fn directivity_code(v: Option<Degrees>) -> u8 {
+    v.map_or(0, |v| (v.0 / 45.0).round().clamp(1.0, 8.0) as u8)
+}
+
+// `ccc` or `sss`, unknown as dots or blanks
+fn course_speed_field(b: &[u8]) -> Option<Option<f32>> {
+    if b.iter().all(|&c| c == b'.' || c == b' ') {
+        return Some(None);
+    }
+    number(b).map(|v| Some(v as f32))
+}
+
+// Area offsets are sent as the square root of hundredths of a degree
+fn area_offset(b: &[u8]) -> Option<f32> {
+    number(b).map(|v| (v * v) as f32 / 100.0)
+}
+
+fn area_offset_code(v: f32) -> u32 {
+    ((v * 100.0).max(0.0).sqrt().round() as u32).min(99)
+}
+
+impl DataExtension {
+    /// Radio range, sent with `RNG` or estimated from `PHG`.
+    pub fn range(&self) -> Option<Miles> {
+        match *self {
+            DataExtension::Range(v) => Some(v),
+            DataExtension::PowerHeightGain { power, height, gain, .. } => Some(phg_range(power, height, gain)),
+            _ => None,
+        }
+    }
+
+    // Extension at the start of a position comment. `Tyy/Cxx` looks like a
+    // course and speed, so it's only tried for area objects.
+    pub(crate) fn parse(s: &str, area: bool) -> Option<DataExtension> {
+        let b = s.as_bytes().get(..EXTENSION_LEN)?;
+        if area {
+            return DataExtension::parse_area(b);
+        }
+        match &b[..3] {
+            b"PHG" => Some(DataExtension::PowerHeightGain {
+                power: Watts(digit(b[3])?.pow(2) as f32),
+                height: height(b[4])?,
+                gain: digit(b[5])? as f32,
+                directivity: directivity(b[6])?,
+            }),
+            b"RNG" => Some(DataExtension::Range(Miles(number(&b[3..])? as f32))),
+            b"DFS" => Some(DataExtension::SignalStrength {
+                strength: digit(b[3])?,
+                height: height(b[4])?,
+                gain: digit(b[5])? as f32,
+                directivity: directivity(b[6])?,
+            }),
+            _ if b[3] == b'/' => {
+                let course = course_speed_field(&b[..3])?;
+                if course.is_some_and(|v| v > 360.0) {
+                    return None;
+                }
+                let speed = course_speed_field(&b[4..])?.map(Knots);
+                Some(DataExtension::CourseSpeed { course: course.map(Degrees), speed })
+            }
+            _ => None,
+        }
+    }
+
+    // `/` in front of the color means 0-9, `1` means 10-15
+    fn parse_area(b: &[u8]) -> Option<DataExtension> {
+        let high = match b[3] {
+            b'/' => 0,
+            b'1' => 10,
+            _ => return None,
+        };
+        let color = digit(b[4])? + high;
+        if color > 15 {
+            return None;
+        }
+        Some(DataExtension::Area {
+            shape: digit(b[0])?,
+            color,
+            lat_offset: area_offset(&b[1..3])?,
+            lng_offset: area_offset(&b[5..7])?,
+        })
+    }
+}
+
+impl FromStr for DataExtension {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != EXTENSION_LEN {
+            return Err(ParseError::InvalidExtension(s.to_string()));
+        }
+        DataExtension::parse(s, false)
+            .or_else(|| DataExtension::parse(s, true))
+            .ok_or_else(|| ParseError::InvalidExtension(s.to_string()))
+    }
+}
+
+impl Display for DataExtension {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            DataExtension::CourseSpeed { course, speed } => {
+                match course {
+                    Some(v) => write!(f, "{:03}/", v.0.round() as u32)?,
+                    None => write!(f, ".../")?,
+                }
+                match speed {
+                    Some(v) => write!(f, "{:03}", v.0.round() as u32),
+                    None => write!(f, "..."),
+                }
+            }
+            DataExtension::PowerHeightGain { power, height, gain, directivity } => {
+                let p = power.0.max(0.0).sqrt().round().min(9.0) as u8;
+                write!(f, "PHG{}{}{}{}", p, height_code(height), gain.round().clamp(0.0, 9.0) as u8,
+                    directivity_code(directivity))
+            }
+            DataExtension::Range(v) => write!(f, "RNG{:04}", v.0.round().clamp(0.0, 9999.0) as u32),
+            DataExtension::SignalStrength { strength, height, gain, directivity } =>
+                write!(f, "DFS{}{}{}{}", strength.min(9), height_code(height), gain.round().clamp(0.0, 9.0) as u8,
+                    directivity_code(directivity)),
+            DataExtension::Area { shape, color, lat_offset, lng_offset } => {
+                let separator = if color >= 10 { '1' } else { '/' };
+                write!(f, "{}{:02}{}{}{:02}", shape.min(9), area_offset_code(lat_offset), separator, color % 10,
+                    area_offset_code(lng_offset))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DataExtension, Degrees, Feet, Knots, Miles, ParseError, Watts};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("PHG5132".parse(), Ok(DataExtension::PowerHeightGain {
+            power: Watts(25.0), height: Feet(20.0), gain: 3.0, directivity: Some(Degrees(90.0)),
+        }));
+        assert_eq!("RNG0050".parse(), Ok(DataExtension::Range(Miles(50.0))));
+        assert_eq!("DFS2360".parse(), Ok(DataExtension::SignalStrength {
+            strength: 2, height: Feet(80.0), gain: 6.0, directivity: None,
+        }));
+        assert_eq!("088/036".parse(), Ok(DataExtension::CourseSpeed {
+            course: Some(Degrees(88.0)), speed: Some(Knots(36.0)),
+        }));
+        assert_eq!(".../   ".parse(), Ok(DataExtension::CourseSpeed { course: None, speed: None }));
+        // Heights go past `9`
+        assert!(matches!("PHG5:30".parse(), Ok(DataExtension::PowerHeightGain { height: Feet(10240.0), .. })));
+    }
+
+    #[test]
+    fn test_parse_area() {
+        assert_eq!(DataExtension::parse("435/305 comment", true), Some(DataExtension::Area {
+            shape: 4, color: 3, lat_offset: 12.25, lng_offset: 0.25,
+        }));
+        assert_eq!(DataExtension::parse("710110", true), None);
+        assert!(matches!(DataExtension::parse("7101105", true), Some(DataExtension::Area { color: 11, .. })));
+        assert_eq!(DataExtension::parse("PHG5132", true), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["PHG513", "PHG5139", "RNG00x0", "361/0x0", "DFS236", "abcdefg", "PHG51320"] {
+            assert_eq!(s.parse::<DataExtension>(), Err(ParseError::InvalidExtension(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["PHG5132", "PHG5:30", "RNG0050", "DFS2360", "088/036", ".../...", "435/305", "7101105"] {
+            assert_eq!(s.parse::<DataExtension>().unwrap().to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{ParseError, PositionReport};
+
+/// Item report, data type identifier `)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemReport {
+    /// Item name, 3 to 9 characters.
+    pub name: String,
+    /// `false` for killed items.
+    pub live: bool,
+    /// Item position, symbol and comment. Items have no timestamp.
+    pub report: PositionReport,
+}
+
+impl FromStr for ItemReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidItem(s.to_string());
+        if !s.starts_with(')') {
+            return Err(err());
+        }
+        // Name is terminated by the first `!` or `_`
+        let end = s.bytes().skip(1).take(10).position(|c| c == b'!' || c == b'_').ok_or_else(err)? + 1;
+        if end < 4 {
+            return Err(err());
+        }
+        let report = PositionReport::parse_body(&s[end + 1..], None, false)?;
+        Ok(ItemReport {
+            name: s[1..end].to_string(),
+            live: s.as_bytes()[end] == b'!',
+            report,
+        })
+    }
+}
+
+impl Display for ItemReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "){}{}", self.name, if self.live { '!' } else { '_' })?;
+        self.report.fmt_body(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ItemReport, ParseError, Symbol};
+
+    #[test]
+    fn test_parse() {
+        let v: ItemReport = ")AID #2!4903.50N/07201.75WA".parse().unwrap();
+        assert_eq!(v.name, "AID #2");
+        assert!(v.live);
+        assert_eq!(v.report.timestamp, None);
+        assert_eq!(v.report.symbol(), Symbol::AidStation);
+
+        let v: ItemReport = ")G/WB4APR_/5L!!<*e7A sTMile 5".parse().unwrap();
+        assert_eq!(v.name, "G/WB4APR");
+        assert!(!v.live);
+        assert!(v.report.compressed);
+        assert_eq!(v.report.comment, "Mile 5");
+
+        let v: ItemReport = ")CP9_4903.50N/07201.75WA".parse().unwrap();
+        assert_eq!(v.name, "CP9");
+        assert!(!v.live);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [")AB!4903.50N/07201.75WA", ")TOOLONGNAME!4903.50N/07201.75WA", "AID #2!4903.50N/07201.75WA"] {
+            assert_eq!(s.parse::<ItemReport>(), Err(ParseError::InvalidItem(s.to_string())));
+        }
+        assert!(")AID #2!4903.50N".parse::<ItemReport>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [")AID #2!4903.50N/07201.75WA", ")G/WB4APR_/5L!!<*e6A sTMile 5"] {
+            assert_eq!(s.parse::<ItemReport>().unwrap().to_string(), s);
+        }
+    }
+}
@@ -11,22 +11,98 @@
 //! This crate defines Rust data types, traits and symbols per 
 //! [APRS v1.01 specification](http://www.aprs.org/doc/APRS101.PDF).
 //! 
-//! It does not provide any parsing or encoding behavior, there are
-//! separate crates for this. The intent here is to enable interoperability 
-//! between different implementations and let client code use them as drop-in 
+//! The intent here is to enable interoperability between different 
+//! implementations and let client code use them as drop-in 
 //! replacements for each other.
 //!
-//! If you're looking for a compartible parser check out the 
-//! [`fap`](https://docs.rs/fap) crate, which is a 
-//! Rust wrapper around Tapio Aaltonen's 
+//! A native parser for TNC2-format packets is included, see [`AprsPacket`]:
+//!
+//! ```
+//! use aprs::{AprsPacket, Packet};
+//!
+//! let packet: AprsPacket = "N0CALL>APRS,WIDE2-1:>Hello".parse().unwrap();
+//! assert_eq!(packet.source(), "N0CALL");
+//! ```
+//!
+//! Another compatible parser is the [`fap`](https://docs.rs/fap) crate, 
+//! which is a Rust wrapper around Tapio Aaltonen's 
 //! [`libfap`](http://www.pakettiradio.net/libfap/) library.
 //!
 use std::borrow::Cow;
 use std::time::SystemTime;
 
 mod units;
-pub use units::{Feet, Meters, Knots, MetersPerSecond, MilesPerHour, KilometersPerHour, 
-  Degrees, Fahrenheits, Celsius, Radians};
+pub use units::{Feet, Meters, Miles, Knots, MetersPerSecond, MilesPerHour, KilometersPerHour, 
+  Degrees, Fahrenheits, Celsius, Radians, Inches, HundredthsOfInch, Millimeters, Hectopascals, Millibars,
+  InchesOfMercury, Percent, Watts, WattsPerSquareMeter, Hertz, Megahertz};
+
+mod error;
+pub use error::ParseError;
+
+mod timestamp;
+pub use timestamp::Timestamp;
+
+mod base91;
+
+mod compressed;
+pub use compressed::{CompressedPosition, CompressedExtension, CompressionType, NmeaSource, CompressionOrigin};
+
+mod extension;
+pub use extension::DataExtension;
+
+mod coverage;
+pub use coverage::Coverage;
+
+mod position;
+pub use position::PositionReport;
+
+mod mic_e;
+pub use mic_e::{MicE, MicEMessage, MicERadio, MicETelemetry};
+
+mod object;
+pub use object::ObjectReport;
+
+mod item;
+pub use item::ItemReport;
+
+mod message;
+pub use message::{Message, MessageKind, ReplyAck};
+
+mod weather;
+pub use weather::{Weather, WeatherReport};
+
+mod peet_bros;
+pub use peet_bros::{PeetBrosReport, PeetBrosFormat};
+
+mod telemetry;
+pub use telemetry::{Telemetry, TelemetrySequence};
+
+mod telemetry_metadata;
+pub use telemetry_metadata::{TelemetryMetadata, TelemetryDefinitions, TelemetryRegistry, Equation, AnalogValue,
+  DigitalValue, DecodedTelemetry};
+
+mod maidenhead;
+
+mod comment;
+pub use comment::{Comment, Dao, Tone};
+
+mod status;
+pub use status::{StatusReport, BeamHeading};
+
+mod query;
+pub use query::{Query, QueryType, QueryFootprint, DirectedQuery, Capabilities};
+
+mod nmea;
+pub use nmea::{NmeaReport, NmeaSentence};
+
+mod bulletin;
+pub use bulletin::{Bulletin, BulletinKind, BulletinBoard, BulletinEntry};
+
+mod packet;
+pub use packet::{AprsPacket, AprsData};
+
+mod session;
+pub use session::{MessageSession, Clock, SystemClock, Transport, RetryPolicy, SessionEvent};
 
 // Latitude and logitude are technically also degrees, 
 // but in APRS packets latlng degrees and course degrees 
@@ -62,6 +138,16 @@ impl Position {
     pub fn coordinates(&self) -> (f32, f32) {
       (self.longitude, self.latitude)
     }
+
+    /// Maidenhead locator of 4, 6 or 8 characters, `None` for other lengths.
+    pub fn to_maidenhead(&self, len: usize) -> Option<String> {
+      maidenhead::encode(self, len)
+    }
+
+    /// Centre of a Maidenhead grid square, with `precision` set from the square size.
+    pub fn from_maidenhead(locator: &str) -> Option<Position> {
+      maidenhead::decode(locator)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -209,14 +295,19 @@ impl Symbol {
     pub fn from_table(table: u8, code: u8) -> Symbol {
         symbol::from_table(table, code)
     }
+
+    /// Symbol table identifier and symbol code, `None` for [`Symbol::Other`].
+    pub fn to_table(&self) -> Option<(u8, u8)> {
+        symbol::to_table(self)
+    }
 }
 
 pub trait Packet {
-    fn source(&self) -> Cow<str>;
+    fn source(&self) -> Cow<'_, str>;
     fn symbol(&self) -> Symbol;
     fn timestamp(&self) -> Option<SystemTime>;
-    fn destination(&self) -> Option<Cow<str>>;
-    fn comment(&self) -> Option<Cow<str>>;
+    fn destination(&self) -> Option<Cow<'_, str>>;
+    fn comment(&self) -> Option<Cow<'_, str>>;
     fn position(&self) -> Option<Position>;
     fn speed(&self) -> Option<Knots>;
     fn course(&self) -> Option<Degrees>;
@@ -225,11 +316,109 @@ pub trait Packet {
     fn wind_direction(&self) -> Option<Degrees>;
     fn wind_speed(&self) -> Option<Knots>;
 
-    // fn message_id(&self) -> Option<&str>;
-    // fn message_addressee(&self) -> Option<&str>;
-    // fn message_text(&self) -> Option<&str>;
-    // fn message_ack(&self) -> Option<bool>;
-    // fn message_rjc(&self) -> Option<bool>;
+    /// Peak wind speed in the last 5 minutes.
+    fn wind_gust(&self) -> Option<Knots> {
+        None
+    }
+
+    fn rain_last_hour(&self) -> Option<HundredthsOfInch> {
+        None
+    }
+
+    fn rain_last_24h(&self) -> Option<HundredthsOfInch> {
+        None
+    }
+
+    fn rain_since_midnight(&self) -> Option<HundredthsOfInch> {
+        None
+    }
+
+    fn humidity(&self) -> Option<Percent> {
+        None
+    }
+
+    /// Barometric pressure, corrected to sea level.
+    fn pressure(&self) -> Option<Hectopascals> {
+        None
+    }
+
+    fn luminosity(&self) -> Option<WattsPerSquareMeter> {
+        None
+    }
+
+    /// Snowfall in the last 24 hours.
+    fn snowfall(&self) -> Option<Inches> {
+        None
+    }
+
+    /// Data extension following an uncompressed position.
+    fn data_extension(&self) -> Option<DataExtension> {
+        None
+    }
+
+    /// Radio range from the `RNG` extension or a compressed position, or estimated from `PHG`.
+    fn range(&self) -> Option<Miles> {
+        None
+    }
+
+    /// Area the station covers according to its range and antenna directivity.
+    fn coverage(&self) -> Option<Coverage> {
+        None
+    }
+
+    /// Altitude, DAO, telemetry and frequency found in the comment, with the
+    /// weather of weather station reports.
+    fn parsed_comment(&self) -> Option<Comment> {
+        None
+    }
+
+    /// Telemetry report, or telemetry embedded in the comment.
+    fn telemetry(&self) -> Option<Telemetry> {
+        None
+    }
+
+    /// Whether the station is capable of receiving messages.
+    fn messaging(&self) -> Option<bool> {
+        None
+    }
+
+    /// Name of the object or item the packet reports on.
+    fn object_name(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    /// Whether the reported object or item has been killed.
+    fn killed(&self) -> Option<bool> {
+        None
+    }
+
+    /// ID of the message, or of the message being acknowledged or rejected.
+    fn message_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn message_addressee(&self) -> Option<&str> {
+        None
+    }
+
+    fn message_text(&self) -> Option<&str> {
+        None
+    }
+
+    /// ID of the message acknowledged by a reply-ack piggy-backed on this one.
+    fn message_reply_ack(&self) -> Option<&str> {
+        None
+    }
+
+    /// `Some(true)` for message acknowledgements.
+    fn message_ack(&self) -> Option<bool> {
+        None
+    }
+
+    /// `Some(true)` for message rejections.
+    fn message_rjc(&self) -> Option<bool> {
+        None
+    }
 
 
     fn latitude(&self) -> Option<f32> {
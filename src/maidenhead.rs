@@ -0,0 +1,127 @@
+// Maidenhead grid locators: pairs of longitude/latitude digits in bases
+// 18 (`A`-`R`), 10, 24 (`a`-`x`) and 10, longitude first.
+
+use crate::{Feet, Position};
+
+// Size of a latitude minute
+const FEET_IN_MINUTE: f32 = 6076.12;
+
+// Cell size in degrees of longitude and latitude after each pair
+const CELLS: [(f32, f32); 4] = [(20.0, 10.0), (2.0, 1.0), (5.0 / 60.0, 2.5 / 60.0), (0.5 / 60.0, 0.25 / 60.0)];
+
+fn pair_base(i: usize) -> u8 {
+    match i {
+        0 => 18,
+        2 => 24,
+        _ => 10,
+    }
+}
+
+fn digit_value(i: usize, c: u8) -> Option<u8> {
+    let v = match i {
+        0 | 2 => c.to_ascii_uppercase().checked_sub(b'A')?,
+        _ => c.checked_sub(b'0')?,
+    };
+    if v < pair_base(i) { Some(v) } else { None }
+}
+
+/// Centre of a 4, 6 or 8 character locator, with the latitude size of the square as precision.
+pub(crate) fn decode(locator: &str) -> Option<Position> {
+    let b = locator.as_bytes();
+    if !matches!(b.len(), 4 | 6 | 8) {
+        return None;
+    }
+    let (mut lng, mut lat) = (-180.0f32, -90.0f32);
+    for (i, pair) in b.chunks(2).enumerate() {
+        let (x, y) = (digit_value(i, pair[0])?, digit_value(i, pair[1])?);
+        lng += x as f32 * CELLS[i].0;
+        lat += y as f32 * CELLS[i].1;
+    }
+    let (w, h) = CELLS[b.len() / 2 - 1];
+    Some(Position::from_latlng_precise(lat + h / 2.0, lng + w / 2.0, Feet(h * 60.0 * FEET_IN_MINUTE)))
+}
+
+/// Locator of `len` characters, 4, 6 or 8, with the subsquare in lower case.
+pub(crate) fn encode(position: &Position, len: usize) -> Option<String> {
+    if !matches!(len, 4 | 6 | 8) || !position.latitude.is_finite() || !position.longitude.is_finite() {
+        return None;
+    }
+    // The antimeridian belongs to field `A`, the North Pole to the top row of squares
+    let mut x = (position.longitude as f64 + 180.0).rem_euclid(360.0);
+    let mut y = (position.latitude as f64 + 90.0).clamp(0.0, 180.0);
+    let mut s = String::with_capacity(len);
+    for (i, &(w, h)) in CELLS.iter().enumerate().take(len / 2) {
+        let max = pair_base(i) as f64 - 1.0;
+        let dx = (x / w as f64).floor().min(max);
+        let dy = (y / h as f64).floor().min(max);
+        x -= dx * w as f64;
+        y -= dy * h as f64;
+        let first = match i {
+            0 => b'A',
+            2 => b'a',
+            _ => b'0',
+        };
+        s.push((first + dx as u8) as char);
+        s.push((first + dy as u8) as char);
+    }
+    Some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use crate::Position;
+
+    #[test]
+    fn test_decode() {
+        let v = decode("IO91").unwrap();
+        assert_abs_diff_eq!(v.latitude, 51.5, epsilon = 0.0001);
+        assert_abs_diff_eq!(v.longitude, -1.0, epsilon = 0.0001);
+        assert_abs_diff_eq!(v.precision.unwrap().0, 364_567.2, epsilon = 0.1);
+
+        let v = decode("io91wm").unwrap();
+        assert_abs_diff_eq!(v.latitude, 51.52083, epsilon = 0.0001);
+        assert_abs_diff_eq!(v.longitude, -0.125, epsilon = 0.0001);
+        assert_abs_diff_eq!(v.precision.unwrap().0, 15_190.3, epsilon = 0.1);
+
+        for s in ["", "IO9", "SO91", "IO91YA", "IOA1", "IO91wm5"] {
+            assert_eq!(decode(s), None);
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        let v = Position::from_latlng(51.97917, -0.45833);
+        assert_eq!(v.to_maidenhead(4).as_deref(), Some("IO91"));
+        assert_eq!(v.to_maidenhead(6).as_deref(), Some("IO91sx"));
+        assert_eq!(v.to_maidenhead(8).as_deref(), Some("IO91sx55"));
+        assert_eq!(v.to_maidenhead(5), None);
+        assert_eq!(Position::from_latlng(-33.86785, 151.20732).to_maidenhead(6).as_deref(), Some("QF56od"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for s in ["IO91sx", "JJ00aa", "AA00aa00", "RR99xx99", "FN31pr", "QF56od27", "BL11"] {
+            let v = Position::from_maidenhead(s).unwrap();
+            assert_eq!(v.to_maidenhead(s.len()).as_deref(), Some(s));
+        }
+        assert_eq!(Position::from_maidenhead("io91SX"), Position::from_maidenhead("IO91sx"));
+        assert_eq!(Position::from_maidenhead("IO91s"), None);
+    }
+
+    #[test]
+    fn test_edges() {
+        // Poles fall in the first and last rows
+        assert_eq!(Position::from_latlng(90.0, 0.0).to_maidenhead(6).as_deref(), Some("JR09ax"));
+        assert_eq!(Position::from_latlng(-90.0, 0.0).to_maidenhead(6).as_deref(), Some("JA00aa"));
+        // Both sides of the antimeridian are the same meridian
+        assert_eq!(Position::from_latlng(0.0, 180.0).to_maidenhead(4).as_deref(), Some("AJ00"));
+        assert_eq!(Position::from_latlng(0.0, -180.0).to_maidenhead(4).as_deref(), Some("AJ00"));
+        assert_eq!(Position::from_latlng(0.0, 179.99).to_maidenhead(4).as_deref(), Some("RJ90"));
+
+        let v = Position::from_maidenhead("RR99xx99").unwrap();
+        assert!(v.latitude < 90.0 && v.longitude < 180.0);
+        assert_abs_diff_eq!(v.precision.unwrap().0, 1519.0, epsilon = 0.1);
+        assert_eq!(Position::from_latlng(f32::NAN, 0.0).to_maidenhead(4), None);
+    }
+}
@@ -0,0 +1,209 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{DirectedQuery, ParseError};
+
+/// Reply-ack trailer of the APRS 1.1 addendum, following `}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplyAck {
+    /// `{MM}`: sender supports reply-acks but has nothing to acknowledge.
+    Supported,
+    /// `{MM}AA`: acknowledges message `AA` received from the addressee.
+    Ack(String),
+}
+
+/// Content of a message frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageKind {
+    /// Message text and the optional ID the recipient has to acknowledge.
+    Text { text: String, id: Option<String>, reply_ack: Option<ReplyAck> },
+    Ack { id: String, reply_ack: Option<ReplyAck> },
+    Reject { id: String, reply_ack: Option<ReplyAck> },
+}
+
+/// Message, data type identifier `:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// Recipient with the padding removed, up to 9 characters.
+    pub addressee: String,
+    pub kind: MessageKind,
+}
+
+impl Message {
+    pub fn text(&self) -> Option<&str> {
+        match &self.kind {
+            MessageKind::Text { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        match &self.kind {
+            MessageKind::Text { id, .. } => id.as_deref(),
+            MessageKind::Ack { id, .. } | MessageKind::Reject { id, .. } => Some(id),
+        }
+    }
+
+    /// ID of the message acknowledged by a piggy-backed reply-ack.
+    pub fn reply_ack_id(&self) -> Option<&str> {
+        match &self.kind {
+            MessageKind::Text { reply_ack: Some(ReplyAck::Ack(id)), .. }
+            | MessageKind::Ack { reply_ack: Some(ReplyAck::Ack(id)), .. }
+            | MessageKind::Reject { reply_ack: Some(ReplyAck::Ack(id)), .. } => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Directed query carried in the message text, such as `?APRSP`.
+    pub fn query(&self) -> Option<DirectedQuery> {
+        self.text().filter(|v| v.starts_with('?'))?.parse().ok()
+    }
+}
+
+fn is_message_id(v: &str) -> bool {
+    (1..=5).contains(&v.len()) && v.bytes().all(|c| c.is_ascii_alphanumeric())
+}
+
+// Splits `MM}AA` and `MM}` reply-ack forms, `None` if `v` is not an ID
+fn parse_id(v: &str) -> Option<(String, Option<ReplyAck>)> {
+    match v.split_once('}') {
+        Some((id, "")) if is_message_id(id) => Some((id.to_string(), Some(ReplyAck::Supported))),
+        Some((id, ack)) if is_message_id(id) && is_message_id(ack) =>
+            Some((id.to_string(), Some(ReplyAck::Ack(ack.to_string())))),
+        None if is_message_id(v) => Some((v.to_string(), None)),
+        _ => None,
+    }
+}
+
+impl FromStr for Message {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidMessage(s.to_string());
+        if !s.starts_with(':') || s.as_bytes().get(10) != Some(&b':') {
+            return Err(err());
+        }
+        let addressee = s.get(1..10).ok_or_else(err)?.trim_end();
+        if addressee.is_empty() {
+            return Err(err());
+        }
+
+        let body = s[11..].trim_end_matches(['\r', '\n']);
+        let kind = match (body.get(..3), body.get(3..).and_then(parse_id)) {
+            (Some("ack"), Some((id, reply_ack))) => MessageKind::Ack { id, reply_ack },
+            (Some("rej"), Some((id, reply_ack))) => MessageKind::Reject { id, reply_ack },
+            _ => match body.rsplit_once('{').and_then(|(text, id)| Some((text, parse_id(id)?))) {
+                Some((text, (id, reply_ack))) =>
+                    MessageKind::Text { text: text.to_string(), id: Some(id), reply_ack },
+                None => MessageKind::Text { text: body.to_string(), id: None, reply_ack: None },
+            },
+        };
+        Ok(Message { addressee: addressee.to_string(), kind })
+    }
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, ":{:<9}:", self.addressee)?;
+        let reply_ack = match &self.kind {
+            MessageKind::Text { text, id: Some(id), reply_ack } => {
+                write!(f, "{}{{{}", text, id)?;
+                reply_ack
+            }
+            MessageKind::Text { text, id: None, .. } => return write!(f, "{}", text),
+            MessageKind::Ack { id, reply_ack } => {
+                write!(f, "ack{}", id)?;
+                reply_ack
+            }
+            MessageKind::Reject { id, reply_ack } => {
+                write!(f, "rej{}", id)?;
+                reply_ack
+            }
+        };
+        match reply_ack {
+            Some(ReplyAck::Supported) => write!(f, "}}"),
+            Some(ReplyAck::Ack(id)) => write!(f, "}}{}", id),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Message, MessageKind, ParseError, ReplyAck};
+
+    #[test]
+    fn test_parse_text() {
+        let v: Message = ":WU2Z     :Testing{003".parse().unwrap();
+        assert_eq!(v.addressee, "WU2Z");
+        assert_eq!(v.kind, MessageKind::Text { text: "Testing".to_string(), id: Some("003".to_string()), reply_ack: None });
+        assert_eq!(v.text(), Some("Testing"));
+        assert_eq!(v.id(), Some("003"));
+
+        let v: Message = ":N0CALL-15:Hello {there!}".parse().unwrap();
+        assert_eq!(v.addressee, "N0CALL-15");
+        assert_eq!(v.kind, MessageKind::Text { text: "Hello {there!}".to_string(), id: None, reply_ack: None });
+        assert_eq!(v.id(), None);
+    }
+
+    #[test]
+    fn test_parse_ack_rej() {
+        let v: Message = ":KB2ICI-14:ack003".parse().unwrap();
+        assert_eq!(v.kind, MessageKind::Ack { id: "003".to_string(), reply_ack: None });
+        assert_eq!(v.id(), Some("003"));
+        assert_eq!(v.text(), None);
+        let v: Message = ":KB2ICI-14:rej003".parse().unwrap();
+        assert_eq!(v.kind, MessageKind::Reject { id: "003".to_string(), reply_ack: None });
+        let v: Message = ":KB2ICI-14:acknowledged".parse().unwrap();
+        assert_eq!(v.text(), Some("acknowledged"));
+    }
+
+    #[test]
+    fn test_parse_reply_ack() {
+        let v: Message = ":WU2Z     :Testing{AB}CD".parse().unwrap();
+        assert_eq!(v.kind, MessageKind::Text {
+            text: "Testing".to_string(),
+            id: Some("AB".to_string()),
+            reply_ack: Some(ReplyAck::Ack("CD".to_string())),
+        });
+        assert_eq!(v.id(), Some("AB"));
+        assert_eq!(v.reply_ack_id(), Some("CD"));
+
+        let v: Message = ":WU2Z     :Testing{AB}".parse().unwrap();
+        assert_eq!(v.id(), Some("AB"));
+        assert_eq!(v.reply_ack_id(), None);
+        assert_eq!(v.kind, MessageKind::Text {
+            text: "Testing".to_string(),
+            id: Some("AB".to_string()),
+            reply_ack: Some(ReplyAck::Supported),
+        });
+
+        let v: Message = ":WU2Z     :ackAB}CD".parse().unwrap();
+        assert_eq!(v.kind, MessageKind::Ack { id: "AB".to_string(), reply_ack: Some(ReplyAck::Ack("CD".to_string())) });
+        assert_eq!(v.reply_ack_id(), Some("CD"));
+
+        let v: Message = ":WU2Z     :rejAB}CD".parse().unwrap();
+        assert_eq!(v.kind, MessageKind::Reject { id: "AB".to_string(), reply_ack: Some(ReplyAck::Ack("CD".to_string())) });
+        assert_eq!(v.reply_ack_id(), Some("CD"));
+
+        let v: Message = ":WU2Z     :Smile {:-}".parse().unwrap();
+        assert_eq!(v.id(), None);
+        assert_eq!(v.text(), Some("Smile {:-}"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [":WU2Z:Testing", ":         :Testing", "WU2Z      :Testing", ":WU2Z"] {
+            assert_eq!(s.parse::<Message>(), Err(ParseError::InvalidMessage(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [":WU2Z     :Testing{003", ":WU2Z     :ack003", ":WU2Z     :rej3", ":BLN1     :Net tonight", ":WU2Z     :",
+                  ":WU2Z     :Testing{AB}CD", ":WU2Z     :Testing{AB}", ":WU2Z     :ackAB}CD", ":WU2Z     :ackAB}",
+                  ":WU2Z     :rejAB}CD", ":WU2Z     :rejAB}"] {
+            assert_eq!(s.parse::<Message>().unwrap().to_string(), s);
+        }
+    }
+}
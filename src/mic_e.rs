@@ -0,0 +1,539 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{base91, Degrees, Feet, Knots, Meters, ParseError, Position, Symbol, Telemetry};
+use crate::position::{ambiguity_from_precision, AMBIGUITY_FEET};
+use crate::telemetry;
+
+/// Mic-E message code, carried by the three message bits of the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicEMessage {
+    OffDuty,
+    EnRoute,
+    InService,
+    Returning,
+    Committed,
+    Special,
+    Priority,
+    /// Custom message 0 to 6.
+    Custom(u8),
+    Emergency,
+    /// Mix of standard and custom message bits.
+    Unknown,
+}
+
+/// Hex encoded 8-bit telemetry channels following the symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicETelemetry {
+    /// `'` and two channels.
+    Two([u8; 2]),
+    /// `` ` `` and five channels.
+    Five([u8; 5]),
+}
+
+impl MicETelemetry {
+    pub fn channels(&self) -> &[u8] {
+        match self {
+            MicETelemetry::Two(v) => v,
+            MicETelemetry::Five(v) => v,
+        }
+    }
+}
+
+/// Kenwood radio that sent the report, from the characters around the comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicERadio {
+    /// `>` before the comment.
+    ThD7,
+    /// `>` before and `=` after the comment.
+    ThD72,
+    /// `>` before and `^` after the comment.
+    ThD74,
+    /// `]` before the comment.
+    TmD700,
+    /// `]` before and `=` after the comment.
+    TmD710,
+}
+
+impl MicERadio {
+    fn prefix(self) -> char {
+        match self {
+            MicERadio::ThD7 | MicERadio::ThD72 | MicERadio::ThD74 => '>',
+            MicERadio::TmD700 | MicERadio::TmD710 => ']',
+        }
+    }
+
+    fn suffix(self) -> &'static str {
+        match self {
+            MicERadio::ThD72 | MicERadio::TmD710 => "=",
+            MicERadio::ThD74 => "^",
+            MicERadio::ThD7 | MicERadio::TmD700 => "",
+        }
+    }
+}
+
+/// Mic-E encoded position report, data type identifiers `` ` `` and `'`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicE {
+    pub position: Position,
+    pub speed: Knots,
+    /// `0` means unknown, `360` is due north.
+    pub course: Degrees,
+    pub symbol_table: u8,
+    pub symbol_code: u8,
+    pub message: MicEMessage,
+    /// GPS fix is current (`` ` ``) rather than old (`'`).
+    pub current: bool,
+    pub altitude: Option<Feet>,
+    pub radio: Option<MicERadio>,
+    pub telemetry: Option<MicETelemetry>,
+    pub comment: String,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MessageBit {
+    Zero,
+    Standard,
+    Custom,
+}
+
+// Decoded destination address character: latitude digit (`None` when
+// blanked out for ambiguity), message bit and the N/+100/W flag
+fn decode_destination_char(c: u8) -> Option<(Option<u8>, MessageBit, bool)> {
+    match c {
+        b'0'..=b'9' => Some((Some(c - b'0'), MessageBit::Zero, false)),
+        b'A'..=b'J' => Some((Some(c - b'A'), MessageBit::Custom, false)),
+        b'K' => Some((None, MessageBit::Custom, false)),
+        b'L' => Some((None, MessageBit::Zero, false)),
+        b'P'..=b'Y' => Some((Some(c - b'P'), MessageBit::Standard, true)),
+        b'Z' => Some((None, MessageBit::Standard, true)),
+        _ => None,
+    }
+}
+
+fn decode_message(bits: [MessageBit; 3]) -> MicEMessage {
+    let has = |kind| bits.contains(&kind);
+    if has(MessageBit::Standard) && has(MessageBit::Custom) {
+        return MicEMessage::Unknown;
+    }
+    let n = bits.iter().fold(0, |acc, &v| acc << 1 | (v != MessageBit::Zero) as u8);
+    match (n, has(MessageBit::Custom)) {
+        (0, _) => MicEMessage::Emergency,
+        (n, true) => MicEMessage::Custom(7 - n),
+        (7, false) => MicEMessage::OffDuty,
+        (6, false) => MicEMessage::EnRoute,
+        (5, false) => MicEMessage::InService,
+        (4, false) => MicEMessage::Returning,
+        (3, false) => MicEMessage::Committed,
+        (2, false) => MicEMessage::Special,
+        _ => MicEMessage::Priority,
+    }
+}
+
+impl MicE {
+    /// Builds a report with a current GPS fix, `None` if `symbol` has no
+    /// table representation.
+    pub fn new(position: Position, speed: Knots, course: Degrees, symbol: Symbol, message: MicEMessage) -> Option<MicE> {
+        let (symbol_table, symbol_code) = symbol.to_table()?;
+        Some(MicE {
+            position,
+            speed,
+            course,
+            symbol_table,
+            symbol_code,
+            message,
+            current: true,
+            altitude: None,
+            radio: None,
+            telemetry: None,
+            comment: String::new(),
+        })
+    }
+
+    pub fn symbol(&self) -> Symbol {
+        Symbol::from_table(self.symbol_table, self.symbol_code)
+    }
+
+    /// Base-91 telemetry embedded in the comment, unlike [`MicE::telemetry`]
+    /// which comes before it.
+    pub fn comment_telemetry(&self) -> Option<Telemetry> {
+        telemetry::split_comment(&self.comment).map(|v| v.0)
+    }
+
+    /// Encodes the report into the destination address and the
+    /// information field.
+    ///
+    /// [`MicEMessage::Unknown`] can't be represented and is sent as
+    /// [`MicEMessage::OffDuty`].
+    pub fn encode(&self) -> (String, String) {
+        (self.encode_destination(), self.to_string())
+    }
+
+    fn encode_destination(&self) -> String {
+        let lat = (self.position.latitude.abs() as f64 * 6000.0).round() as u32;
+        let digits = [lat / 60_000, lat / 6000 % 10, lat % 6000 / 1000, lat % 1000 / 100, lat % 100 / 10, lat % 10];
+        let ambiguity = ambiguity_from_precision(self.position.precision);
+
+        let (bits, kind) = match self.message {
+            MicEMessage::Emergency => (0, MessageBit::Zero),
+            MicEMessage::Custom(n) => (7 - n.min(6), MessageBit::Custom),
+            MicEMessage::OffDuty | MicEMessage::Unknown => (7, MessageBit::Standard),
+            MicEMessage::EnRoute => (6, MessageBit::Standard),
+            MicEMessage::InService => (5, MessageBit::Standard),
+            MicEMessage::Returning => (4, MessageBit::Standard),
+            MicEMessage::Committed => (3, MessageBit::Standard),
+            MicEMessage::Special => (2, MessageBit::Standard),
+            MicEMessage::Priority => (1, MessageBit::Standard),
+        };
+        // North, longitude offset and West flags
+        let lng_deg = (self.position.longitude.abs() as f64 * 6000.0).round() as u32 / 6000;
+        let flags = [self.position.latitude >= 0.0, !(10..=99).contains(&lng_deg), self.position.longitude < 0.0];
+
+        (0..6).map(|i| {
+                let bit = if i < 3 {
+                    if bits & (4 >> i) != 0 { kind } else { MessageBit::Zero }
+                } else if flags[i - 3] {
+                    MessageBit::Standard
+                } else {
+                    MessageBit::Zero
+                };
+                let blank = i >= 6 - ambiguity;
+                match (bit, blank) {
+                    (MessageBit::Zero, false) => (b'0' + digits[i] as u8) as char,
+                    (MessageBit::Custom, false) => (b'A' + digits[i] as u8) as char,
+                    (MessageBit::Standard, false) => (b'P' + digits[i] as u8) as char,
+                    (MessageBit::Zero, true) => 'L',
+                    (MessageBit::Custom, true) => 'K',
+                    (MessageBit::Standard, true) => 'Z',
+                }
+            })
+            .collect()
+    }
+
+    /// Decodes a Mic-E report from the destination callsign and the
+    /// information field, including its data type identifier.
+    pub fn decode(destination: &str, info: &str) -> Result<MicE, ParseError> {
+        let err = || ParseError::InvalidMicE(info.to_string());
+        let dest = destination.split('-').next().unwrap_or("").as_bytes();
+        if dest.len() != 6 {
+            return Err(ParseError::InvalidMicE(destination.to_string()));
+        }
+        let mut chars = [(None, MessageBit::Zero, false); 6];
+        for (i, &c) in dest.iter().enumerate() {
+            chars[i] = decode_destination_char(c).ok_or_else(|| ParseError::InvalidMicE(destination.to_string()))?;
+        }
+
+        // Blanked out latitude digits have to be the rightmost ones
+        let ambiguity = chars.iter().rev().take_while(|v| v.0.is_none()).count();
+        if ambiguity > 4 || chars[..6 - ambiguity].iter().any(|v| v.0.is_none()) {
+            return Err(ParseError::InvalidMicE(destination.to_string()));
+        }
+        let digit = |i: usize| chars[i].0.unwrap_or(0) as u32;
+        let lat_deg = digit(0) * 10 + digit(1);
+        let lat_hundredths = digit(2) * 1000 + digit(3) * 100 + digit(4) * 10 + digit(5);
+        let mut latitude = lat_deg as f32 + lat_hundredths as f32 / 6000.0;
+        if lat_deg > 90 || lat_hundredths >= 6000 || latitude > 90.0 {
+            return Err(ParseError::InvalidMicE(destination.to_string()));
+        }
+        if !chars[3].2 {
+            latitude = -latitude;
+        }
+
+        let b = info.as_bytes();
+        let current = match b.first() {
+            Some(b'`') | Some(0x1c) => true,
+            Some(b'\'') | Some(0x1d) => false,
+            _ => return Err(err()),
+        };
+        if b.len() < 9 || b[1..9].iter().any(|&c| c < 28) || !info.is_char_boundary(9) {
+            return Err(err());
+        }
+
+        let mut lng_deg = (b[1] - 28) as u32;
+        if chars[4].2 {
+            lng_deg += 100;
+        }
+        if (180..=189).contains(&lng_deg) {
+            lng_deg -= 80;
+        } else if (190..=199).contains(&lng_deg) {
+            lng_deg -= 190;
+        }
+        let mut lng_min = (b[2] - 28) as u32;
+        if lng_min >= 60 {
+            lng_min -= 60;
+        }
+        let lng_hundredths = (b[3] - 28) as u32;
+        if lng_deg > 179 || lng_min >= 60 || lng_hundredths >= 100 {
+            return Err(err());
+        }
+        // Longitude ambiguity follows the latitude one
+        let lng_hundredths = match ambiguity {
+            0 => lng_min * 100 + lng_hundredths,
+            1 => lng_min * 100 + lng_hundredths / 10 * 10,
+            2 => lng_min * 100,
+            3 => lng_min / 10 * 1000,
+            _ => 0,
+        };
+        let mut longitude = lng_deg as f32 + lng_hundredths as f32 / 6000.0;
+        if chars[5].2 {
+            longitude = -longitude;
+        }
+
+        let sp = (b[4] - 28) as u32;
+        let dc = (b[5] - 28) as u32;
+        let se = (b[6] - 28) as u32;
+        let mut speed = sp * 10 + dc / 10;
+        let mut course = (dc % 10) * 100 + se;
+        if speed >= 800 {
+            speed -= 800;
+        }
+        if course >= 400 {
+            course -= 400;
+        }
+
+        let mut position = Position::from_latlng(latitude, longitude);
+        if ambiguity > 0 {
+            position.set_precision(Feet(AMBIGUITY_FEET[ambiguity - 1]));
+        }
+        let (telemetry, rest) = split_telemetry(&info[9..]);
+        let (altitude, comment) = split_altitude(rest);
+        let (radio, comment) = split_radio(comment);
+
+        Ok(MicE {
+            position,
+            speed: Knots(speed as f32),
+            course: Degrees(course as f32),
+            symbol_table: b[8],
+            symbol_code: b[7],
+            message: decode_message([chars[0].1, chars[1].1, chars[2].1]),
+            current,
+            altitude,
+            radio,
+            telemetry,
+            comment,
+        })
+    }
+}
+
+// Writes the information field, the destination has to be encoded separately
+impl Display for MicE {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let lng = (self.position.longitude.abs() as f64 * 6000.0).round() as u32;
+        let (deg, min, hundredths) = (lng / 6000, lng % 6000 / 100, lng % 100);
+        let deg = match deg {
+            0..=9 => deg + 90,
+            10..=99 => deg,
+            100..=109 => deg - 20,
+            _ => deg - 100,
+        };
+        let min = if min < 10 { min + 60 } else { min };
+
+        let speed = self.speed.0.round().clamp(0.0, 799.0) as u32;
+        let course = self.course.0.round().clamp(0.0, 360.0) as u32;
+        let sp = if speed < 200 { speed / 10 + 80 } else { speed / 10 };
+        // Course is offset by 400 to keep the byte printable
+        let dc = speed % 10 * 10 + course / 100 + 4;
+        let se = course % 100;
+
+        let bytes = [deg, min, hundredths, sp, dc, se].map(|v| char::from((v + 28) as u8));
+        write!(f, "{}", if self.current { '`' } else { '\'' })?;
+        bytes.iter().try_for_each(|c| write!(f, "{}", c))?;
+        write!(f, "{}{}", self.symbol_code as char, self.symbol_table as char)?;
+        if let Some(v) = &self.telemetry {
+            write!(f, "{}", if let MicETelemetry::Two(_) = v { '\'' } else { '`' })?;
+            v.channels().iter().try_for_each(|v| write!(f, "{:02X}", v))?;
+        }
+
+        let altitude = match self.altitude {
+            Some(v) => format!("{}}}", base91::encode((Meters::from(v).0 + 10_000.0).round().max(0.0) as u32, 3)),
+            None => String::new(),
+        };
+        match (self.radio, self.comment.chars().next()) {
+            (Some(radio), _) => write!(f, "{}{}{}{}", radio.prefix(), altitude, self.comment, radio.suffix()),
+            (None, Some(c)) if is_type_indicator(c) && !altitude.is_empty() =>
+                write!(f, "{}{}{}", c, altitude, &self.comment[1..]),
+            _ => write!(f, "{}{}", altitude, self.comment),
+        }
+    }
+}
+
+// Telemetry follows the symbol table identifier: `` ` `` and five hex
+// encoded channels or `'` and two
+fn split_telemetry(s: &str) -> (Option<MicETelemetry>, &str) {
+    let len = match s.as_bytes().first() {
+        Some(b'`') => 5,
+        Some(b'\'') => 2,
+        _ => return (None, s),
+    };
+    let hex = match s.get(1..1 + 2 * len) {
+        Some(v) if v.bytes().all(|c| c.is_ascii_hexdigit()) => v,
+        _ => return (None, s),
+    };
+    let channel = |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap_or(0);
+    let telemetry = match len {
+        2 => MicETelemetry::Two([channel(0), channel(1)]),
+        _ => MicETelemetry::Five([channel(0), channel(1), channel(2), channel(3), channel(4)]),
+    };
+    (Some(telemetry), &s[1 + 2 * len..])
+}
+
+// Radio type indicators that may precede the altitude
+fn is_type_indicator(c: char) -> bool {
+    matches!(c, '>' | ']' | '`' | '\'')
+}
+
+// Altitude is three base-91 digits followed by `}`, meters relative to
+// 10 km below sea level. It follows an optional radio type indicator.
+fn split_altitude(s: &str) -> (Option<Feet>, String) {
+    let b = s.as_bytes();
+    let starts: &[usize] = if s.starts_with(is_type_indicator) { &[0, 1] } else { &[0] };
+    for &start in starts {
+        if b.len() >= start + 4 && b[start + 3] == b'}'
+            && let Some(v) = base91::decode(&b[start..start + 3]) {
+            let altitude = Feet::from(Meters(v as f32 - 10_000.0));
+            return (Some(altitude), format!("{}{}", &s[..start], &s[start + 4..]));
+        }
+    }
+    (None, s.to_string())
+}
+
+// Kenwood radios put `>` or `]` in front of the comment, and some of them
+// `=` or `^` after it
+fn split_radio(s: String) -> (Option<MicERadio>, String) {
+    let (kenwood, rest) = match s.strip_prefix('>') {
+        Some(rest) => (true, rest),
+        None => match s.strip_prefix(']') {
+            Some(rest) => (false, rest),
+            None => return (None, s),
+        },
+    };
+    let radio = match (kenwood, rest.chars().last()) {
+        (true, Some('=')) => MicERadio::ThD72,
+        (true, Some('^')) => MicERadio::ThD74,
+        (true, _) => MicERadio::ThD7,
+        (false, Some('=')) => MicERadio::TmD710,
+        (false, _) => MicERadio::TmD700,
+    };
+    let rest = &rest[..rest.len() - radio.suffix().len()];
+    (Some(radio), rest.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Degrees, Feet, Knots, Meters, MicE, MicEMessage, MicERadio, MicETelemetry, ParseError, Position, Symbol};
+
+    #[test]
+    fn test_decode() {
+        // 33 25.64 N, 112 07.74 W, 20 knots, 251 degrees
+        let v = MicE::decode("S32UVT", "`(_fn\"Oj/").unwrap();
+        assert_abs_diff_eq!(v.position.latitude, 33.42733, epsilon = 0.00001);
+        assert_abs_diff_eq!(v.position.longitude, -112.129, epsilon = 0.00001);
+        assert_eq!(v.speed, Knots(20.0));
+        assert_eq!(v.course, Degrees(251.0));
+        assert_eq!(v.symbol(), Symbol::Jeep);
+        assert_eq!(v.message, MicEMessage::Returning);
+        assert!(v.current);
+        assert_eq!(v.altitude, None);
+        assert_eq!(v.comment, "");
+    }
+
+    #[test]
+    fn test_decode_altitude_and_comment() {
+        let v = MicE::decode("T2SP0W-9", "`c51!f?>/]\"4f}Comment=").unwrap();
+        assert_abs_diff_eq!(v.position.latitude, 42.50117, epsilon = 0.00001);
+        assert_abs_diff_eq!(v.position.longitude, -71.42017, epsilon = 0.00001);
+        assert_eq!(v.speed, Knots(57.0));
+        assert_eq!(v.course, Degrees(35.0));
+        assert_eq!(v.symbol(), Symbol::Car);
+        assert_eq!(v.message, MicEMessage::InService);
+        assert!(!MicE::decode("T2SP0W-9", "'c51!f?>/").unwrap().current);
+        assert_abs_diff_eq!(v.altitude.unwrap().0, Feet::from(Meters(79.0)).0, epsilon = 0.01);
+        assert_eq!(v.radio, Some(MicERadio::TmD710));
+        assert_eq!(v.comment, "Comment");
+    }
+
+    #[test]
+    fn test_decode_messages() {
+        let message = |dest: &str| MicE::decode(dest, "`(_fn\"Oj/").unwrap().message;
+        assert_eq!(message("PPPU6T"), MicEMessage::OffDuty);
+        assert_eq!(message("002U6T"), MicEMessage::Emergency);
+        assert_eq!(message("AB2U6T"), MicEMessage::Custom(1));
+        assert_eq!(message("AP2U6T"), MicEMessage::Unknown);
+        assert_eq!(message("03PU6T"), MicEMessage::Priority);
+    }
+
+    #[test]
+    fn test_decode_ambiguity() {
+        let v = MicE::decode("S32U6Z", "`(_fn\"Oj/").unwrap();
+        assert_eq!(v.position.precision, Some(Feet(607.6)));
+        assert!(v.position.longitude < 0.0);
+        assert!(MicE::decode("S32ZZT", "`(_fn\"Oj/").is_err());
+    }
+
+    #[test]
+    fn test_info_round_trip() {
+        for info in ["`(_fn\"Oj/", "`c51qf?>/]\"4f}Comment=", "'c51qf?>/\"4f}", "`\x7fX\x1c\x6c\x20\x1c>/",
+            "`(_fn\"Oj/>Text^", "`(_fn\"Oj/]Text^", "`(_fn\"Oj/]"] {
+            assert_eq!(MicE::decode("T2SP0W", info).unwrap().to_string(), info);
+        }
+    }
+
+    #[test]
+    fn test_decode_telemetry() {
+        let v = MicE::decode("S32UVT", "`(_fn\"Oj/`0A1B2C3D4E>Text").unwrap();
+        assert_eq!(v.telemetry, Some(MicETelemetry::Five([0x0a, 0x1b, 0x2c, 0x3d, 0x4e])));
+        assert_eq!(v.radio, Some(MicERadio::ThD7));
+        assert_eq!(v.comment, "Text");
+        let v = MicE::decode("S32UVT", "`(_fn\"Oj/'FF00\"4f}").unwrap();
+        assert_eq!(v.telemetry, Some(MicETelemetry::Two([0xff, 0x00])));
+        assert_eq!(v.telemetry.unwrap().channels(), [0xff, 0x00]);
+        assert!(v.altitude.is_some());
+        let v = MicE::decode("S32UVT", "`(_fn\"Oj/`_\"").unwrap();
+        assert_eq!(v.telemetry, None);
+        assert_eq!(v.comment, "`_\"");
+    }
+
+    #[test]
+    fn test_encode() {
+        let position = Position::from_latlng(33.42733, -112.129);
+        let v = MicE::new(position, Knots(20.0), Degrees(251.0), Symbol::Jeep, MicEMessage::Returning).unwrap();
+        assert_eq!(v.encode(), ("S32UVT".to_string(), "`(_fn\"Oj/".to_string()));
+
+        let mut v = MicE::new(Position::from_latlng(-5.5, 7.25), Knots(0.0), Degrees(0.0),
+            Symbol::Car, MicEMessage::Custom(2)).unwrap();
+        v.position.set_precision(Feet(6076.1));
+        let (dest, _) = v.encode();
+        assert_eq!(dest, "A5D0ZL");
+        assert!(MicE::new(position, Knots(0.0), Degrees(0.0), Symbol::Other, MicEMessage::OffDuty).is_none());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        for (lat, lng) in [(33.42733, -112.129), (-5.5, 7.25), (0.0, 179.5), (89.99, -105.01), (45.0, -10.0)] {
+            for message in [MicEMessage::Emergency, MicEMessage::Priority, MicEMessage::Custom(0), MicEMessage::OffDuty] {
+                let mut v = MicE::new(Position::from_latlng(lat, lng), Knots(123.0), Degrees(359.0),
+                    Symbol::Ambulance, message).unwrap();
+                v.altitude = Some(Feet(12_345.0));
+                v.telemetry = Some(MicETelemetry::Five([1, 2, 3, 4, 5]));
+                v.radio = Some(MicERadio::ThD74);
+                v.comment = "Hello".to_string();
+                let (dest, info) = v.encode();
+                let w = MicE::decode(&dest, &info).unwrap();
+                assert_abs_diff_eq!(w.position.latitude, lat, epsilon = 0.0001);
+                assert_abs_diff_eq!(w.position.longitude, lng, epsilon = 0.0001);
+                assert_abs_diff_eq!(w.altitude.unwrap().0, 12_345.0, epsilon = 2.0);
+                assert_eq!((w.speed, w.course, w.message), (v.speed, v.course, v.message));
+                assert_eq!(w.telemetry, v.telemetry);
+                assert_eq!((w.radio, &w.comment), (v.radio, &v.comment));
+                assert_eq!(w.symbol(), Symbol::Ambulance);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(MicE::decode("S32U6", "`(_fn\"Oj/"), Err(ParseError::InvalidMicE("S32U6".to_string())));
+        assert_eq!(MicE::decode("S32U6T", "`(_fn\"O"), Err(ParseError::InvalidMicE("`(_fn\"O".to_string())));
+        assert!(MicE::decode("S32U6T", "!(_fn\"Oj/").is_err());
+        assert!(MicE::decode("S3xU6T", "`(_fn\"Oj/").is_err());
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Degrees, Feet, Knots, Meters, ParseError, Position, Timestamp};
+use crate::timestamp::{days_from_civil, days_in_month};
+
+/// NMEA sentence types decoded from APRS packets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmeaSentence {
+    /// Recommended minimum data: time, position, speed, course and date.
+    Rmc,
+    /// Fix data: time, position and altitude.
+    Gga,
+    /// Geographic position: position and time.
+    Gll,
+    /// Track and ground speed.
+    Vtg,
+    /// Waypoint position and name.
+    Wpl,
+}
+
+impl NmeaSentence {
+    fn from_name(name: &str) -> Option<NmeaSentence> {
+        match name {
+            "RMC" => Some(NmeaSentence::Rmc),
+            "GGA" => Some(NmeaSentence::Gga),
+            "GLL" => Some(NmeaSentence::Gll),
+            "VTG" => Some(NmeaSentence::Vtg),
+            "WPL" => Some(NmeaSentence::Wpl),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            NmeaSentence::Rmc => "RMC",
+            NmeaSentence::Gga => "GGA",
+            NmeaSentence::Gll => "GLL",
+            NmeaSentence::Vtg => "VTG",
+            NmeaSentence::Wpl => "WPL",
+        }
+    }
+}
+
+/// Raw GPS sentence, data type identifier `$`.
+///
+/// Fields are kept as sent and decoded on access. Position and time
+/// accessors return `None` when the receiver reports no valid fix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NmeaReport {
+    /// Talker ID, `GP` for GPS.
+    pub talker: String,
+    pub sentence: NmeaSentence,
+    /// Fields following the sentence type.
+    pub fields: Vec<String>,
+    /// Whether the sentence carries a `*hh` checksum.
+    pub checksum: bool,
+}
+
+// XOR of the bytes between `$` and `*`
+fn checksum(s: &str) -> u8 {
+    s.bytes().fold(0, |acc, c| acc ^ c)
+}
+
+// Whether `info` is a sentence type decoded by `NmeaReport`
+pub(crate) fn is_supported(info: &str) -> bool {
+    let b = info.as_bytes();
+    b.len() >= 6 && b[0] == b'$' && b[1..3].iter().all(u8::is_ascii_uppercase)
+        && info.get(3..6).and_then(NmeaSentence::from_name).is_some()
+}
+
+// `ddmm.mm` or `dddmm.mm` with its hemisphere
+fn parse_coordinate(v: &str, hemisphere: &str, degree_digits: usize, negative: &str) -> Option<f32> {
+    if v.len() < degree_digits + 2 || !v.is_char_boundary(degree_digits) {
+        return None;
+    }
+    let degrees: f32 = v[..degree_digits].parse().ok()?;
+    let minutes: f32 = v[degree_digits..].parse().ok()?;
+    if !(0.0..60.0).contains(&minutes) {
+        return None;
+    }
+    let value = degrees + minutes / 60.0;
+    match hemisphere {
+        h if h == negative => Some(-value),
+        "N" | "E" => Some(value),
+        _ => None,
+    }
+}
+
+impl NmeaReport {
+    fn field(&self, i: usize) -> Option<&str> {
+        self.fields.get(i).map(String::as_str).filter(|v| !v.is_empty())
+    }
+
+    fn number(&self, i: usize) -> Option<f32> {
+        self.field(i)?.parse().ok()
+    }
+
+    // Latitude and longitude fields starting at `i`
+    fn latlng(&self, i: usize) -> Option<Position> {
+        let lat = parse_coordinate(self.field(i)?, self.field(i + 1)?, 2, "S")?;
+        let lng = parse_coordinate(self.field(i + 2)?, self.field(i + 3)?, 3, "W")?;
+        if lat.abs() > 90.0 || lng.abs() > 180.0 {
+            return None;
+        }
+        Some(Position::from_latlng(lat, lng))
+    }
+
+    /// Whether the receiver had a fix, from the RMC and GLL status or the GGA fix quality.
+    pub fn valid(&self) -> bool {
+        match self.sentence {
+            NmeaSentence::Rmc => self.field(1) == Some("A"),
+            NmeaSentence::Gll => self.field(5) == Some("A"),
+            NmeaSentence::Gga => self.field(5).is_some_and(|v| v != "0"),
+            NmeaSentence::Vtg | NmeaSentence::Wpl => true,
+        }
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        if !self.valid() {
+            return None;
+        }
+        match self.sentence {
+            NmeaSentence::Rmc => self.latlng(2),
+            NmeaSentence::Gga => self.latlng(1),
+            NmeaSentence::Gll | NmeaSentence::Wpl => self.latlng(0),
+            NmeaSentence::Vtg => None,
+        }
+    }
+
+    /// Speed over ground.
+    pub fn speed(&self) -> Option<Knots> {
+        match self.sentence {
+            NmeaSentence::Rmc if self.valid() => self.number(6).map(Knots),
+            NmeaSentence::Vtg => self.number(4).map(Knots),
+            _ => None,
+        }
+    }
+
+    /// True course over ground.
+    pub fn course(&self) -> Option<Degrees> {
+        match self.sentence {
+            NmeaSentence::Rmc if self.valid() => self.number(7).map(Degrees),
+            NmeaSentence::Vtg => self.number(0).map(Degrees),
+            _ => None,
+        }
+    }
+
+    /// Altitude above mean sea level, GGA only.
+    pub fn altitude(&self) -> Option<Feet> {
+        match self.sentence {
+            NmeaSentence::Gga if self.valid() && self.field(9) == Some("M") =>
+                self.number(8).map(|v| Feet::from(Meters(v))),
+            _ => None,
+        }
+    }
+
+    /// Waypoint name, WPL only.
+    pub fn waypoint(&self) -> Option<&str> {
+        match self.sentence {
+            NmeaSentence::Wpl => self.field(4),
+            _ => None,
+        }
+    }
+
+    /// UTC time of the fix, with fractional seconds dropped.
+    pub fn time(&self) -> Option<Timestamp> {
+        let v = match self.sentence {
+            NmeaSentence::Rmc | NmeaSentence::Gga if self.valid() => self.field(0)?,
+            NmeaSentence::Gll if self.valid() => self.field(4)?,
+            _ => return None,
+        };
+        let v = v.split('.').next()?;
+        Timestamp::parse(&format!("{}h", v)).ok()
+    }
+
+    /// UTC date of the fix as year, month and day, RMC only.
+    pub fn date(&self) -> Option<(i64, u32, u32)> {
+        let v = match self.sentence {
+            NmeaSentence::Rmc if self.valid() => self.field(8)?,
+            _ => return None,
+        };
+        if v.len() != 6 || !v.bytes().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let (day, month, year): (u32, u32, i64) = (v[..2].parse().ok()?, v[2..4].parse().ok()?, v[4..].parse().ok()?);
+        // Two-digit years, the GPS era starts in 1980
+        let year = if year < 80 { 2000 + year } else { 1900 + year };
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some((year, month, day))
+    }
+
+    /// Time of the fix. RMC sentences carry the date, other sentences are
+    /// resolved to the latest matching time not later than `reference`.
+    pub fn to_system_time(&self, reference: SystemTime) -> Option<SystemTime> {
+        let time = self.time()?;
+        match (self.date(), time) {
+            (Some((year, month, day)), Timestamp::HourMinuteSecond { hour, minute, second }) => {
+                let secs = days_from_civil(year, month, day) * 86_400
+                    + hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+                Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+            }
+            _ => time.to_system_time(reference),
+        }
+    }
+}
+
+impl FromStr for NmeaReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidNmea(s.to_string());
+        let line = s.trim_end_matches(['\r', '\n']);
+        if !is_supported(line) {
+            return Err(err());
+        }
+        let body = &line[1..];
+        let (body, checksum) = match body.rsplit_once('*') {
+            Some((body, hex)) => {
+                let expected = u8::from_str_radix(hex, 16).ok().filter(|_| hex.len() == 2).ok_or_else(err)?;
+                if checksum(body) != expected {
+                    return Err(err());
+                }
+                (body, true)
+            }
+            None => (body, false),
+        };
+        let fields = match body[5..].strip_prefix(',') {
+            Some(v) => v.split(',').map(str::to_string).collect(),
+            None if body.len() == 5 => Vec::new(),
+            None => return Err(err()),
+        };
+        Ok(NmeaReport {
+            talker: body[..2].to_string(),
+            sentence: NmeaSentence::from_name(&body[2..5]).ok_or_else(err)?,
+            fields,
+            checksum,
+        })
+    }
+}
+
+impl Display for NmeaReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut body = format!("{}{}", self.talker, self.sentence.name());
+        for v in &self.fields {
+            body.push(',');
+            body.push_str(v);
+        }
+        write!(f, "${}", body)?;
+        if self.checksum {
+            write!(f, "*{:02X}", checksum(&body))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use crate::{Degrees, Knots, NmeaReport, NmeaSentence, ParseError, Timestamp};
+    use crate::timestamp::days_from_civil;
+
+    const RMC: &str = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A";
+    const GGA: &str = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47";
+
+    #[test]
+    fn test_parse_rmc() {
+        let v: NmeaReport = RMC.parse().unwrap();
+        assert_eq!(v.talker, "GP");
+        assert_eq!(v.sentence, NmeaSentence::Rmc);
+        let p = v.position().unwrap();
+        assert_abs_diff_eq!(p.latitude, 48.1173, epsilon = 0.0001);
+        assert_abs_diff_eq!(p.longitude, 11.51667, epsilon = 0.0001);
+        assert_eq!(v.speed(), Some(Knots(22.4)));
+        assert_eq!(v.course(), Some(Degrees(84.4)));
+        assert_eq!(v.time(), Some(Timestamp::HourMinuteSecond { hour: 12, minute: 35, second: 19 }));
+        assert_eq!(v.date(), Some((1994, 3, 23)));
+        let expected = days_from_civil(1994, 3, 23) as u64 * 86_400 + 12 * 3600 + 35 * 60 + 19;
+        assert_eq!(v.to_system_time(UNIX_EPOCH), Some(UNIX_EPOCH + Duration::from_secs(expected)));
+
+        // No fix
+        let v: NmeaReport = "$GPRMC,123519,V,,,,,,,230394,,".parse().unwrap();
+        assert!(!v.valid());
+        assert_eq!(v.position(), None);
+        assert_eq!(v.speed(), None);
+    }
+
+    #[test]
+    fn test_parse_other_sentences() {
+        let v: NmeaReport = GGA.parse().unwrap();
+        assert_abs_diff_eq!(v.position().unwrap().latitude, 48.1173, epsilon = 0.0001);
+        assert_abs_diff_eq!(v.altitude().unwrap().0, 1789.37, epsilon = 0.1);
+        assert_eq!(v.date(), None);
+
+        let v: NmeaReport = "$GPGLL,3751.65,S,14507.36,E,225444,A".parse().unwrap();
+        let p = v.position().unwrap();
+        assert_abs_diff_eq!(p.latitude, -37.86083, epsilon = 0.0001);
+        assert_abs_diff_eq!(p.longitude, 145.1227, epsilon = 0.0001);
+        assert_eq!(v.time(), Some(Timestamp::HourMinuteSecond { hour: 22, minute: 54, second: 44 }));
+        assert!(!v.checksum);
+
+        let v: NmeaReport = "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K".parse().unwrap();
+        assert_eq!(v.course(), Some(Degrees(54.7)));
+        assert_eq!(v.speed(), Some(Knots(5.5)));
+        assert_eq!(v.position(), None);
+
+        let v: NmeaReport = "$GPWPL,4917.16,N,12310.64,W,003".parse().unwrap();
+        assert_eq!(v.waypoint(), Some("003"));
+        assert_abs_diff_eq!(v.position().unwrap().longitude, -123.1773, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let bad_checksum = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6B";
+        for s in [bad_checksum, "$GPGSA,A,3", "$ULTW0000", "GPRMC,123519", "$GPRMC123519", "$GPGLL,1*4"] {
+            assert_eq!(s.parse::<NmeaReport>(), Err(ParseError::InvalidNmea(s.to_string())));
+        }
+        // Malformed fields decode to nothing
+        let v: NmeaReport = "$GPGLL,37x1.65,S,14507.36,E,225444,A".parse().unwrap();
+        assert_eq!(v.position(), None);
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [RMC, GGA, "$GPGLL,3751.65,S,14507.36,E,225444,A", "$GPWPL,4917.16,N,12310.64,W,003"] {
+            assert_eq!(s.parse::<NmeaReport>().unwrap().to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{ParseError, PositionReport, Timestamp};
+
+// Placeholder for objects without a meaningful timestamp, per APRS 1.1
+const NO_TIMESTAMP: Timestamp = Timestamp::DayHourMinute { day: 11, hour: 11, minute: 11, zulu: true };
+
+/// Object report, data type identifier `;`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectReport {
+    /// Object name with the padding removed, up to 9 characters.
+    pub name: String,
+    /// `false` for killed objects.
+    pub live: bool,
+    /// Object timestamp, position, symbol and comment.
+    pub report: PositionReport,
+}
+
+impl FromStr for ObjectReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidObject(s.to_string());
+        if !s.starts_with(';') {
+            return Err(err());
+        }
+        let name = s.get(1..10).ok_or_else(err)?;
+        let live = match s.as_bytes().get(10) {
+            Some(b'*') => true,
+            Some(b'_') => false,
+            _ => return Err(err()),
+        };
+        let ts = s.get(11..18).ok_or_else(|| ParseError::InvalidTimestamp(s[11..].to_string()))?;
+        let report = PositionReport::parse_body(&s[18..], Some(Timestamp::parse(ts)?), false)?;
+        Ok(ObjectReport {
+            name: name.trim_end().to_string(),
+            live,
+            report,
+        })
+    }
+}
+
+impl Display for ObjectReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, ";{:<9}{}{}", self.name, if self.live { '*' } else { '_' },
+            self.report.timestamp.unwrap_or(NO_TIMESTAMP))?;
+        self.report.fmt_body(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Degrees, ObjectReport, ParseError, Symbol, Timestamp};
+
+    #[test]
+    fn test_parse() {
+        let v: ObjectReport = ";LEADER   *092345z4903.50N/07201.75W>088/036".parse().unwrap();
+        assert_eq!(v.name, "LEADER");
+        assert!(v.live);
+        assert_eq!(v.report.timestamp, Some(Timestamp::DayHourMinute { day: 9, hour: 23, minute: 45, zulu: true }));
+        assert_eq!(v.report.symbol(), Symbol::Car);
+        assert_abs_diff_eq!(v.report.position.latitude, 49.05833, epsilon = 0.00001);
+        assert_eq!(v.report.course(), Some(Degrees(88.0)));
+        assert_eq!(v.report.comment, "");
+
+        let v: ObjectReport = ";SHELTER 1_111111z/5L!!<*e7H sTClosed".parse().unwrap();
+        assert_eq!(v.name, "SHELTER 1");
+        assert!(!v.live);
+        assert!(v.report.compressed);
+        assert_eq!(v.report.symbol(), Symbol::Hotel);
+        assert_eq!(v.report.comment, "Closed");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in [";LEADER*092345z4903.50N/07201.75W>", ";LEADER   x092345z4903.50N/07201.75W>", "LEADER   *"] {
+            assert_eq!(s.parse::<ObjectReport>(), Err(ParseError::InvalidObject(s.to_string())));
+        }
+        assert!(";LEADER   *0923".parse::<ObjectReport>().is_err());
+        assert!(";LEADER   *092345z4903.50N".parse::<ObjectReport>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [";LEADER   *092345z4903.50N/07201.75W>088/036", ";SHELTER 1_111111z/5L!!<*e6H sTClosed"] {
+            assert_eq!(s.parse::<ObjectReport>().unwrap().to_string(), s);
+        }
+        let mut v: ObjectReport = ";ICP      *092345z4903.50N/07201.75Wc".parse().unwrap();
+        v.report.timestamp = None;
+        v.live = false;
+        assert_eq!(v.to_string(), ";ICP      _111111z4903.50N/07201.75Wc");
+    }
+}
@@ -0,0 +1,799 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use crate::{Bulletin, Capabilities, Comment, Coverage, DataExtension, Degrees, Fahrenheits, Feet, Hectopascals,
+    HundredthsOfInch, Inches, ItemReport, Knots, Message, MessageKind, MetersPerSecond, MicE, Miles, NmeaReport,
+    ObjectReport, Packet, ParseError, PeetBrosReport, Percent, Position, PositionReport, Query, StatusReport, Symbol,
+    Telemetry, WattsPerSquareMeter, Weather, WeatherReport};
+use crate::nmea;
+
+// Third-party layers decoded before giving up, real traffic has one or two
+const MAX_THIRD_PARTY_DEPTH: usize = 8;
+
+/// Decoded information field of a packet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AprsData {
+    Position(PositionReport),
+    MicE(MicE),
+    Object(ObjectReport),
+    Item(ItemReport),
+    Message(Message),
+    Bulletin(Bulletin),
+    Weather(WeatherReport),
+    PeetBros(PeetBrosReport),
+    Telemetry(Telemetry),
+    Status(StatusReport),
+    Query(Query),
+    Capabilities(Capabilities),
+    Nmea(NmeaReport),
+    /// Packet relayed by a gateway, `}` followed by its TNC2 header and payload.
+    ThirdParty(Box<AprsPacket>),
+    /// Data type this crate can't decode, kept verbatim.
+    Unknown(String),
+}
+
+impl AprsData {
+    // `depth` is the number of third-party layers around the payload
+    fn parse(destination: &str, info: &str, depth: usize) -> Result<AprsData, ParseError> {
+        if info.is_empty() {
+            return Err(ParseError::EmptyInformationField);
+        }
+        // Payloads that don't decode are kept verbatim, as for unknown data types
+        let unknown = |_| AprsData::Unknown(info.to_string());
+        match info.as_bytes()[0] {
+            b'!' | b'=' | b'/' | b'@' => Ok(AprsData::Position(info.parse()?)),
+            b'`' | b'\'' | 0x1c | 0x1d => Ok(AprsData::MicE(MicE::decode(destination, info)?)),
+            b';' => Ok(AprsData::Object(info.parse()?)),
+            b')' => Ok(AprsData::Item(info.parse()?)),
+            b'>' => Ok(AprsData::Status(info.parse()?)),
+            b'?' => Ok(info.parse().map_or_else(unknown, AprsData::Query)),
+            b'<' => Ok(info.parse().map_or_else(unknown, AprsData::Capabilities)),
+            b'}' if depth >= MAX_THIRD_PARTY_DEPTH => Err(ParseError::InvalidThirdParty(info.to_string())),
+            b'}' => match AprsPacket::parse_nested(&info[1..], depth + 1) {
+                Ok(v) => Ok(AprsData::ThirdParty(Box::new(v))),
+                Err(_) => Err(ParseError::InvalidThirdParty(info.to_string())),
+            },
+            b'_' => Ok(info.parse().map_or_else(unknown, AprsData::Weather)),
+            b'#' => Ok(info.parse().map_or_else(unknown, AprsData::PeetBros)),
+            b'T' if info.starts_with("T#") => Ok(info.parse().map_or_else(unknown, AprsData::Telemetry)),
+            b'$' if info.starts_with("$ULTW") => Ok(info.parse().map_or_else(unknown, AprsData::PeetBros)),
+            b'$' if nmea::is_supported(info) => Ok(AprsData::Nmea(info.parse()?)),
+            b':' => match info.parse() {
+                Ok(v) => Ok(AprsData::Bulletin(v)),
+                Err(_) => Ok(AprsData::Message(info.parse()?)),
+            },
+            _ => Ok(AprsData::Unknown(info.to_string())),
+        }
+    }
+
+    // Position report carried by positions, objects and items
+    fn position_report(&self) -> Option<&PositionReport> {
+        match self {
+            AprsData::Position(v) => Some(v),
+            AprsData::Object(v) => Some(&v.report),
+            AprsData::Item(v) => Some(&v.report),
+            _ => None,
+        }
+    }
+
+    // Free text comment, and whether it may embed telemetry, altitude and
+    // the other comment extensions
+    fn comment(&self) -> Option<(&str, bool)> {
+        match (self, self.position_report()) {
+            (_, Some(v)) => Some((&v.comment, true)),
+            (AprsData::MicE(v), _) => Some((&v.comment, true)),
+            (AprsData::Weather(v), _) => Some((&v.comment, false)),
+            (AprsData::Telemetry(v), _) => Some((&v.comment, false)),
+            (AprsData::Status(v), _) => Some((&v.text, false)),
+            _ => None,
+        }
+    }
+
+    // Weather of positionless reports and weather station positions
+    fn weather(&self) -> Option<Weather> {
+        match self {
+            AprsData::Weather(v) => Some(v.weather),
+            AprsData::PeetBros(v) => Some(v.weather()),
+            v => v.position_report().and_then(PositionReport::weather),
+        }
+    }
+}
+
+impl Display for AprsData {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AprsData::Position(v) => write!(f, "{}", v),
+            AprsData::MicE(v) => write!(f, "{}", v),
+            AprsData::Object(v) => write!(f, "{}", v),
+            AprsData::Item(v) => write!(f, "{}", v),
+            AprsData::Message(v) => write!(f, "{}", v),
+            AprsData::Bulletin(v) => write!(f, "{}", v),
+            AprsData::Weather(v) => write!(f, "{}", v),
+            AprsData::PeetBros(v) => write!(f, "{}", v),
+            AprsData::Telemetry(v) => write!(f, "{}", v),
+            AprsData::Status(v) => write!(f, "{}", v),
+            AprsData::Query(v) => write!(f, "{}", v),
+            AprsData::Capabilities(v) => write!(f, "{}", v),
+            AprsData::Nmea(v) => write!(f, "{}", v),
+            AprsData::ThirdParty(v) => write!(f, "}}{}", v),
+            AprsData::Unknown(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Owned packet parsed from a TNC2 `SOURCE>DEST,PATH:payload` line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AprsPacket {
+    pub source: String,
+    pub destination: String,
+    pub path: Vec<String>,
+    pub data: AprsData,
+}
+
+impl AprsPacket {
+    pub fn parse(line: &str) -> Result<AprsPacket, ParseError> {
+        AprsPacket::parse_nested(line, 0)
+    }
+
+    fn parse_nested(line: &str, depth: usize) -> Result<AprsPacket, ParseError> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let (header, info) = line.split_once(':').ok_or(ParseError::MissingHeader)?;
+        let (source, rest) = header.split_once('>').ok_or(ParseError::MissingHeader)?;
+        if !is_callsign(source) {
+            return Err(ParseError::InvalidSource(source.to_string()));
+        }
+
+        let mut parts = rest.split(',');
+        let destination = parts.next().unwrap_or("");
+        if !is_callsign(destination) {
+            return Err(ParseError::InvalidDestination(destination.to_string()));
+        }
+        let path = parts
+            .map(|v| {
+                if is_path_element(v) {
+                    Ok(v.to_string())
+                } else {
+                    Err(ParseError::InvalidPath(v.to_string()))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(AprsPacket {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            path,
+            data: AprsData::parse(destination, info, depth)?,
+        })
+    }
+
+    /// Packet carried by a third-party frame, one layer down.
+    pub fn third_party(&self) -> Option<&AprsPacket> {
+        match &self.data {
+            AprsData::ThirdParty(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Originating packet with all third-party layers removed, `self` if there are none.
+    pub fn innermost(&self) -> &AprsPacket {
+        let mut packet = self;
+        while let Some(v) = packet.third_party() {
+            packet = v;
+        }
+        packet
+    }
+
+    /// Owned version of [`AprsPacket::innermost`].
+    pub fn into_innermost(self) -> AprsPacket {
+        let mut packet = self;
+        while let AprsData::ThirdParty(v) = packet.data {
+            packet = *v;
+        }
+        packet
+    }
+
+    /// Wraps the packet in a third-party frame with the given outer header.
+    pub fn wrap(self, source: &str, destination: &str, path: Vec<String>) -> AprsPacket {
+        AprsPacket {
+            source: source.to_string(),
+            destination: destination.to_string(),
+            path,
+            data: AprsData::ThirdParty(Box::new(self)),
+        }
+    }
+
+    /// Third-party frame for gating an APRS-IS packet to RF: the inner path
+    /// is replaced by `TCPIP,IGATE*` and the IGate becomes the outer source.
+    pub fn gate_to_rf(mut self, igate: &str, destination: &str, path: Vec<String>) -> AprsPacket {
+        self.path = vec!["TCPIP".to_string(), format!("{}*", igate)];
+        self.wrap(igate, destination, path)
+    }
+}
+
+fn is_callsign(v: &str) -> bool {
+    !v.is_empty() && v.len() <= 9 && v.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-')
+}
+
+fn is_path_element(v: &str) -> bool {
+    !v.is_empty() && v.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'*')
+}
+
+impl FromStr for AprsPacket {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AprsPacket::parse(s)
+    }
+}
+
+impl Display for AprsPacket {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}>{}", self.source, self.destination)?;
+        for v in &self.path {
+            write!(f, ",{}", v)?;
+        }
+        write!(f, ":{}", self.data)
+    }
+}
+
+impl Packet for AprsPacket {
+    fn source(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.source)
+    }
+
+    fn symbol(&self) -> Symbol {
+        if let Some(v) = self.data.position_report() {
+            return v.symbol();
+        }
+        match &self.data {
+            AprsData::MicE(v) => v.symbol(),
+            AprsData::Status(v) => v.symbol().unwrap_or(Symbol::Other),
+            _ => Symbol::Other,
+        }
+    }
+
+    fn timestamp(&self) -> Option<SystemTime> {
+        let ts = match &self.data {
+            AprsData::Weather(v) => v.timestamp,
+            AprsData::Status(v) => v.timestamp?,
+            AprsData::Nmea(v) => return v.to_system_time(SystemTime::now()),
+            v => v.position_report().and_then(|v| v.timestamp)?,
+        };
+        ts.to_system_time(SystemTime::now())
+    }
+
+    fn destination(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(&self.destination))
+    }
+
+    fn comment(&self) -> Option<Cow<'_, str>> {
+        // Extensions are only embedded in position and Mic-E comments
+        let (comment, embedded) = self.data.comment()?;
+        let comment = match Comment::parse(comment) {
+            v if embedded && v.text != comment => Cow::Owned(v.text),
+            _ => Cow::Borrowed(comment),
+        };
+        if comment.is_empty() { None } else { Some(comment) }
+    }
+
+    fn position(&self) -> Option<Position> {
+        // `!DAO!` adds precision to uncompressed and Mic-E positions, compressed
+        // ones are already more precise
+        let with_dao = |v: Position, comment: &str| match Comment::parse(comment).dao {
+            Some(dao) => dao.apply(v),
+            None => v,
+        };
+        match (&self.data, self.data.position_report()) {
+            (_, Some(v)) if v.compressed => Some(v.position),
+            (_, Some(v)) => Some(with_dao(v.position, &v.comment)),
+            (AprsData::MicE(v), _) => Some(with_dao(v.position, &v.comment)),
+            (AprsData::Status(v), _) => v.position(),
+            (AprsData::Nmea(v), _) => v.position(),
+            _ => None,
+        }
+    }
+
+    fn speed(&self) -> Option<Knots> {
+        match (&self.data, self.data.position_report()) {
+            (_, Some(v)) => v.speed(),
+            (AprsData::MicE(v), _) => Some(v.speed),
+            (AprsData::Nmea(v), _) => v.speed(),
+            _ => None,
+        }
+    }
+
+    fn course(&self) -> Option<Degrees> {
+        match (&self.data, self.data.position_report()) {
+            (_, Some(v)) => v.course(),
+            // Zero course means unknown in Mic-E
+            (AprsData::MicE(v), _) => Some(v.course).filter(|v| v.0 != 0.0),
+            (AprsData::Nmea(v), _) => v.course(),
+            _ => None,
+        }
+    }
+
+    fn altitude(&self) -> Option<Feet> {
+        match (&self.data, self.data.position_report()) {
+            (_, Some(v)) => v.altitude(),
+            (AprsData::MicE(v), _) => v.altitude.or_else(|| Comment::parse(&v.comment).altitude),
+            (AprsData::Nmea(v), _) => v.altitude(),
+            _ => None,
+        }
+    }
+
+    fn temperature(&self) -> Option<Fahrenheits> {
+        self.data.weather()?.temperature
+    }
+
+    fn wind_direction(&self) -> Option<Degrees> {
+        self.data.weather()?.wind_direction
+    }
+
+    fn wind_speed(&self) -> Option<Knots> {
+        let v = self.data.weather()?.wind_speed?;
+        Some(Knots::from(MetersPerSecond::from(v)))
+    }
+
+    fn wind_gust(&self) -> Option<Knots> {
+        let v = self.data.weather()?.wind_gust?;
+        Some(Knots::from(MetersPerSecond::from(v)))
+    }
+
+    fn rain_last_hour(&self) -> Option<HundredthsOfInch> {
+        self.data.weather()?.rain_last_hour
+    }
+
+    fn rain_last_24h(&self) -> Option<HundredthsOfInch> {
+        self.data.weather()?.rain_last_24h
+    }
+
+    fn rain_since_midnight(&self) -> Option<HundredthsOfInch> {
+        self.data.weather()?.rain_since_midnight
+    }
+
+    fn humidity(&self) -> Option<Percent> {
+        self.data.weather()?.humidity
+    }
+
+    fn pressure(&self) -> Option<Hectopascals> {
+        self.data.weather()?.pressure
+    }
+
+    fn luminosity(&self) -> Option<WattsPerSquareMeter> {
+        self.data.weather()?.luminosity
+    }
+
+    fn snowfall(&self) -> Option<Inches> {
+        self.data.weather()?.snowfall
+    }
+
+    fn data_extension(&self) -> Option<DataExtension> {
+        self.data.position_report()?.extension
+    }
+
+    fn range(&self) -> Option<Miles> {
+        self.data.position_report()?.range()
+    }
+
+    fn coverage(&self) -> Option<Coverage> {
+        self.data.position_report()?.coverage()
+    }
+
+    fn parsed_comment(&self) -> Option<Comment> {
+        let mut comment = Comment::parse(self.data.comment()?.0);
+        comment.weather = self.data.weather();
+        Some(comment)
+    }
+
+    fn telemetry(&self) -> Option<Telemetry> {
+        match (&self.data, self.data.position_report()) {
+            (_, Some(v)) => v.comment_telemetry(),
+            (AprsData::MicE(v), _) => v.comment_telemetry(),
+            (AprsData::Telemetry(v), _) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    fn messaging(&self) -> Option<bool> {
+        match &self.data {
+            AprsData::Position(v) => Some(v.messaging),
+            _ => None,
+        }
+    }
+
+    fn object_name(&self) -> Option<Cow<'_, str>> {
+        match &self.data {
+            AprsData::Object(v) => Some(Cow::Borrowed(&v.name)),
+            AprsData::Item(v) => Some(Cow::Borrowed(&v.name)),
+            _ => None,
+        }
+    }
+
+    fn killed(&self) -> Option<bool> {
+        match &self.data {
+            AprsData::Object(v) => Some(!v.live),
+            AprsData::Item(v) => Some(!v.live),
+            _ => None,
+        }
+    }
+
+    fn message_id(&self) -> Option<&str> {
+        match &self.data {
+            AprsData::Message(v) => v.id(),
+            _ => None,
+        }
+    }
+
+    fn message_reply_ack(&self) -> Option<&str> {
+        match &self.data {
+            AprsData::Message(v) => v.reply_ack_id(),
+            _ => None,
+        }
+    }
+
+    fn message_addressee(&self) -> Option<&str> {
+        match &self.data {
+            AprsData::Message(v) => Some(&v.addressee),
+            _ => None,
+        }
+    }
+
+    fn message_text(&self) -> Option<&str> {
+        match &self.data {
+            AprsData::Message(v) => v.text(),
+            _ => None,
+        }
+    }
+
+    fn message_ack(&self) -> Option<bool> {
+        match &self.data {
+            AprsData::Message(v) => Some(matches!(v.kind, MessageKind::Ack { .. })),
+            _ => None,
+        }
+    }
+
+    fn message_rjc(&self) -> Option<bool> {
+        match &self.data {
+            AprsData::Message(v) => Some(matches!(v.kind, MessageKind::Reject { .. })),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AprsData, AprsPacket, BulletinKind, Coverage, DataExtension, Degrees, DirectedQuery, Fahrenheits,
+        Feet, Hectopascals, HundredthsOfInch, Knots, Megahertz, Miles, Packet, Percent, ParseError, QueryType, Symbol,
+        TelemetrySequence};
+
+    #[test]
+    fn test_parse_header() {
+        let p = AprsPacket::parse("N0CALL-9>APRS,WIDE1-1,WIDE2-1*,qAR,T2TEST:>hello").unwrap();
+        assert_eq!(p.source, "N0CALL-9");
+        assert_eq!(p.destination, "APRS");
+        assert_eq!(p.path, vec!["WIDE1-1", "WIDE2-1*", "qAR", "T2TEST"]);
+        assert!(matches!(p.data, AprsData::Status(_)));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:,test").unwrap().data, AprsData::Unknown(",test".to_string()));
+    }
+
+    #[test]
+    fn test_packet_trait() {
+        let p: AprsPacket = "N0CALL>APRS:>hello\r\n".parse().unwrap();
+        assert_eq!(p.source(), "N0CALL");
+        assert_eq!(p.destination().unwrap(), "APRS");
+        assert_eq!(p.symbol(), Symbol::Other);
+        assert_eq!(p.position(), None);
+    }
+
+    #[test]
+    fn test_position_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:=4903.50N/07201.75W-Home").unwrap();
+        assert_eq!(p.symbol(), Symbol::HouseQth);
+        assert_eq!(p.comment().unwrap(), "Home");
+        assert_eq!(p.messaging(), Some(true));
+        assert_abs_diff_eq!(p.latitude().unwrap(), 49.05833, epsilon = 0.00001);
+        assert!(p.timestamp().is_none());
+
+        let p = AprsPacket::parse("N0CALL>APRS:!/5L!!<*e7>7P[").unwrap();
+        assert_eq!(p.course(), Some(Degrees(88.0)));
+        assert!(p.speed().is_some());
+        assert_eq!(p.altitude(), None);
+
+        let p = AprsPacket::parse("N0CALL>APRS:@092345z4903.50N/07201.75W-").unwrap();
+        assert!(p.timestamp().is_some());
+        assert_eq!(p.comment(), None);
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:!4903.50N").unwrap_err(),
+            ParseError::InvalidPosition("4903.50N".to_string()));
+    }
+
+    #[test]
+    fn test_extension_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W>088/036Driving").unwrap();
+        assert_eq!(p.course(), Some(Degrees(88.0)));
+        assert_eq!(p.speed(), Some(Knots(36.0)));
+        assert_eq!(p.comment().unwrap(), "Driving");
+
+        let p = AprsPacket::parse("N0CALL>APRS:;DIGI     *111111z4903.50N/07201.75W#RNG0025").unwrap();
+        assert_eq!(p.range(), Some(Miles(25.0)));
+        assert_eq!(p.data_extension(), Some(DataExtension::Range(Miles(25.0))));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:>hello").unwrap().data_extension(), None);
+
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W#PHG5132").unwrap();
+        assert_abs_diff_eq!(p.range().unwrap().0, 7.95, epsilon = 0.01);
+        assert!(matches!(p.coverage(), Some(Coverage::Sector { direction: Degrees(90.0), .. })));
+    }
+
+    #[test]
+    fn test_comment_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W>Mobile /A=001234 146.520MHz!W52!").unwrap();
+        assert_eq!(p.altitude(), Some(Feet(1234.0)));
+        assert_eq!(p.comment().unwrap(), "Mobile");
+        assert_abs_diff_eq!(p.latitude().unwrap(), 49.05842, epsilon = 0.00001);
+        let c = p.parsed_comment().unwrap();
+        assert_eq!(c.frequency, Some(Megahertz(146.52)));
+        assert!(c.dao.is_some());
+
+        let p = AprsPacket::parse("N0CALL>APRS:!/5L!!<*e7>7P[!W52!").unwrap();
+        let q = AprsPacket::parse("N0CALL>APRS:!/5L!!<*e7>7P[").unwrap();
+        assert!(p.parsed_comment().unwrap().dao.is_some());
+        assert_eq!(p.position(), q.position());
+
+        let p = AprsPacket::parse("N0CALL>APRS:>Net 146.520MHz T100").unwrap();
+        assert_eq!(p.comment().unwrap(), "Net 146.520MHz T100");
+        assert_eq!(p.parsed_comment().unwrap().text, "Net");
+        assert_eq!(AprsPacket::parse("N0CALL>APRS::N1CALL   :hi").unwrap().parsed_comment(), None);
+
+        // Weather only comes from weather stations
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W>P123 Mobile").unwrap();
+        assert_eq!(p.comment().unwrap(), "P123 Mobile");
+        assert_eq!(p.parsed_comment().unwrap().weather, None);
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W_090/010g015t032 Davis").unwrap();
+        let c = p.parsed_comment().unwrap();
+        assert_eq!(c.weather.unwrap().temperature, Some(Fahrenheits(32.0)));
+        assert_eq!(c.text, "Davis");
+    }
+
+    #[test]
+    fn test_mic_e_packet() {
+        let line = "N0CALL-9>T2SP0W-9,WIDE1-1:`c51qf?>/]\"4f}Comment=";
+        let p = AprsPacket::parse(line).unwrap();
+        assert_eq!(p.symbol(), Symbol::Car);
+        assert_abs_diff_eq!(p.latitude().unwrap(), 42.50117, epsilon = 0.00001);
+        assert_eq!(p.course(), Some(Degrees(35.0)));
+        assert!(p.altitude().is_some());
+        assert_eq!(p.comment().unwrap(), "Comment");
+        assert_eq!(p.to_string(), line);
+        assert!(AprsPacket::parse("N0CALL>APRS:`c51qf?>/").is_err());
+    }
+
+    #[test]
+    fn test_object_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:;SHELTER 1_092345z4903.50N/07201.75WhClosed").unwrap();
+        assert_eq!(p.source(), "N0CALL");
+        assert_eq!(p.object_name().unwrap(), "SHELTER 1");
+        assert_eq!(p.killed(), Some(true));
+        assert_eq!(p.symbol(), Symbol::Hospital);
+        assert_eq!(p.comment().unwrap(), "Closed");
+        assert!(p.timestamp().is_some());
+        assert_eq!(p.messaging(), None);
+        assert!(AprsPacket::parse("N0CALL>APRS:;SHELTER").is_err());
+
+        let p = AprsPacket::parse("N0CALL>APRS:)AID #2!4903.50N/07201.75WA").unwrap();
+        assert_eq!(p.object_name().unwrap(), "AID #2");
+        assert_eq!(p.killed(), Some(false));
+        assert_eq!(p.symbol(), Symbol::AidStation);
+        assert!(p.position().is_some());
+    }
+
+    #[test]
+    fn test_message_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS::WU2Z     :Testing{003").unwrap();
+        assert_eq!(p.message_addressee(), Some("WU2Z"));
+        assert_eq!(p.message_text(), Some("Testing"));
+        assert_eq!(p.message_id(), Some("003"));
+        assert_eq!(p.message_ack(), Some(false));
+        assert_eq!(p.message_rjc(), Some(false));
+        assert_eq!(p.position(), None);
+        assert_eq!(p.message_reply_ack(), None);
+
+        let p = AprsPacket::parse("N0CALL>APRS::WU2Z     :Testing{AB}CD").unwrap();
+        assert_eq!(p.message_id(), Some("AB"));
+        assert_eq!(p.message_reply_ack(), Some("CD"));
+
+        let p = AprsPacket::parse("WU2Z>APRS::N0CALL   :ack003").unwrap();
+        assert_eq!(p.message_id(), Some("003"));
+        assert_eq!(p.message_text(), None);
+        assert_eq!(p.message_ack(), Some(true));
+
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W-").unwrap();
+        assert_eq!(p.message_ack(), None);
+        assert!(AprsPacket::parse("N0CALL>APRS::WU2Z:Testing").is_err());
+    }
+
+    #[test]
+    fn test_weather_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:_10090556c220s004g005t077r000p000P000h50b09900wRSW").unwrap();
+        assert!(matches!(p.data, AprsData::Weather(_)));
+        assert_eq!(p.temperature(), Some(Fahrenheits(77.0)));
+        assert_eq!(p.wind_direction(), Some(Degrees(220.0)));
+        assert_abs_diff_eq!(p.wind_speed().unwrap().0, 3.476, epsilon = 0.001);
+        assert_abs_diff_eq!(p.wind_gust().unwrap().0, 4.345, epsilon = 0.001);
+        assert_eq!(p.rain_last_hour(), Some(HundredthsOfInch(0.0)));
+        assert_eq!(p.rain_last_24h(), Some(HundredthsOfInch(0.0)));
+        assert_eq!(p.rain_since_midnight(), Some(HundredthsOfInch(0.0)));
+        assert_eq!(p.humidity(), Some(Percent(50.0)));
+        assert_eq!(p.pressure(), Some(Hectopascals(990.0)));
+        assert_eq!(p.luminosity(), None);
+        assert_eq!(p.snowfall(), None);
+        assert_eq!(p.comment().as_deref(), Some("wRSW"));
+        assert!(p.timestamp().is_some());
+        assert_eq!(p.position(), None);
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:_1009").unwrap().data, AprsData::Unknown("_1009".to_string()));
+
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W_090/010g015t032Davis").unwrap();
+        assert_eq!(p.temperature(), Some(Fahrenheits(32.0)));
+        assert!(p.position().is_some());
+        assert_eq!(p.comment().as_deref(), Some("Davis"));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W-").unwrap().temperature(), None);
+
+        let p = AprsPacket::parse("N0CALL>APRS:$ULTW0031003702CE0069----000086A00001----011901CC00000005").unwrap();
+        assert!(matches!(p.data, AprsData::PeetBros(_)));
+        assert_abs_diff_eq!(p.temperature().unwrap().0, 71.8, epsilon = 0.001);
+        assert_abs_diff_eq!(p.wind_gust().unwrap().0, 2.646, epsilon = 0.001);
+
+        // Undecodable payloads are kept as unknown data
+        for info in ["#W1", "$ULTW00", "#Net"] {
+            let p = AprsPacket::parse(&format!("N0CALL>APRS:{}", info)).unwrap();
+            assert_eq!(p.data, AprsData::Unknown(info.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_telemetry_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:T#005,199,000,255,073,123,01101001Balloon").unwrap();
+        match &p.data {
+            AprsData::Telemetry(v) => assert_eq!(v.analog[0], Some(199.0)),
+            v => panic!("unexpected {:?}", v),
+        }
+        assert_eq!(p.comment().as_deref(), Some("Balloon"));
+        assert_eq!(p.telemetry().unwrap().analog[0], Some(199.0));
+        assert_eq!(p.to_string(), "N0CALL>APRS:T#005,199,000,255,073,123,01101001Balloon");
+
+        let p = AprsPacket::parse("N0CALL>APRS:T#001,x,2").unwrap();
+        assert_eq!(p.data, AprsData::Unknown("T#001,x,2".to_string()));
+
+        let p = AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W-Balloon |ss11223344|up").unwrap();
+        assert_eq!(p.comment().as_deref(), Some("Balloon up"));
+        assert_eq!(p.telemetry().unwrap().sequence, TelemetrySequence::Number(7544));
+
+        let p = AprsPacket::parse("N0CALL>APRS:;BALLOON  *092345z4903.50N/07201.75WO|!!!!|").unwrap();
+        assert_eq!(p.comment(), None);
+        assert_eq!(p.telemetry().unwrap().analog[0], Some(0.0));
+
+        let p = AprsPacket::parse("N0CALL>T2SP0W:`c51qf?>/|ss11|Hello").unwrap();
+        assert_eq!(p.comment().as_deref(), Some("Hello"));
+        assert!(p.telemetry().is_some());
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:!4903.50N/07201.75W-|x|").unwrap().telemetry(), None);
+    }
+
+    #[test]
+    fn test_status_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:>IO91SX/G Portable^B7").unwrap();
+        assert_eq!(p.comment().as_deref(), Some("Portable"));
+        assert_eq!(p.symbol(), Symbol::GridSquare);
+        let pos = p.position().unwrap();
+        assert_abs_diff_eq!(pos.latitude, 51.97917, epsilon = 0.0001);
+        assert!(p.precision().is_some());
+
+        let p = AprsPacket::parse("N0CALL>APRS:>092345zOn the air").unwrap();
+        assert_eq!(p.comment().as_deref(), Some("On the air"));
+        assert!(p.timestamp().is_some());
+        assert_eq!(p.position(), None);
+
+        // Non-ASCII text, also inside a third-party packet
+        assert!(AprsPacket::parse("N0CALL>APRS:>IO9é/").is_ok());
+        assert!(AprsPacket::parse("N0CALL>APRS:}N1CALL>APRS,TCPIP,N0CALL*:>IO9é/").is_ok());
+    }
+
+    #[test]
+    fn test_bulletin_packet() {
+        let p = AprsPacket::parse("WU2Z>APRS::BLN4WX   :Severe storm warning").unwrap();
+        match &p.data {
+            AprsData::Bulletin(v) => assert_eq!(v.kind, BulletinKind::Group { line: 4, name: "WX".to_string() }),
+            v => panic!("unexpected {:?}", v),
+        }
+        assert_eq!(p.message_text(), None);
+
+        // Addressees that only look like bulletins stay messages
+        let p = AprsPacket::parse("WU2Z>APRS::BLNX1    :Hi{1").unwrap();
+        assert_eq!(p.message_addressee(), Some("BLNX1"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(AprsPacket::parse("N0CALL>APRS"), Err(ParseError::MissingHeader));
+        assert_eq!(AprsPacket::parse("N0CALL:>hello"), Err(ParseError::MissingHeader));
+        assert_eq!(AprsPacket::parse("N0 CALL>APRS:>x"), Err(ParseError::InvalidSource("N0 CALL".to_string())));
+        assert_eq!(AprsPacket::parse("N0CALL>:>x"), Err(ParseError::InvalidDestination("".to_string())));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS,,WIDE:>x"), Err(ParseError::InvalidPath("".to_string())));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:"), Err(ParseError::EmptyInformationField));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let line = "N0CALL-9>APRS,WIDE1-1,WIDE2-1*:>hello";
+        assert_eq!(AprsPacket::parse(line).unwrap().to_string(), line);
+    }
+
+    #[test]
+    fn test_query_packet() {
+        let p = AprsPacket::parse("N0CALL>APRS:?IGATE?").unwrap();
+        assert!(matches!(&p.data, AprsData::Query(v) if v.query == QueryType::IGate));
+
+        let p = AprsPacket::parse("N0CALL>APRS::KH2Z     :?APRSP{12").unwrap();
+        let AprsData::Message(m) = &p.data else { panic!("not a message") };
+        assert_eq!(m.query(), Some(DirectedQuery::Position));
+
+        let p = AprsPacket::parse("KH2Z>APRS:<IGATE,MSG_CNT=30,LOC_CNT=10").unwrap();
+        assert!(matches!(&p.data, AprsData::Capabilities(v) if v.get("MSG_CNT") == Some("30")));
+        assert_eq!(p.to_string(), "KH2Z>APRS:<IGATE,MSG_CNT=30,LOC_CNT=10");
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:?x").unwrap().data, AprsData::Unknown("?x".to_string()));
+        assert_eq!(AprsPacket::parse("N0CALL>APRS:<").unwrap().data, AprsData::Unknown("<".to_string()));
+    }
+
+    #[test]
+    fn test_nmea_packet() {
+        let p = AprsPacket::parse(
+            "N0CALL>GPSLJ:$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A").unwrap();
+        assert!(matches!(p.data, AprsData::Nmea(_)));
+        assert_abs_diff_eq!(p.latitude().unwrap(), 48.1173, epsilon = 0.0001);
+        assert_eq!(p.speed(), Some(Knots(22.4)));
+        assert_eq!(p.course(), Some(Degrees(84.4)));
+        assert!(p.timestamp().is_some());
+
+        let p = AprsPacket::parse("N0CALL>GPS:$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,").unwrap();
+        assert_abs_diff_eq!(p.altitude().unwrap().0, 1789.37, epsilon = 0.1);
+
+        assert!(matches!(AprsPacket::parse("N0CALL>APRS:$ULTW0000000001FF000427C70002CCD30001026E003A050F00040000")
+            .unwrap().data, AprsData::PeetBros(_)));
+        assert!(matches!(AprsPacket::parse("N0CALL>APRS:$GPGSA,A,3").unwrap().data, AprsData::Unknown(_)));
+        assert!(matches!(AprsPacket::parse("N0CALL>APRS:$GPGLL,1*4"), Err(ParseError::InvalidNmea(_))));
+    }
+
+    #[test]
+    fn test_third_party_packet() {
+        let line = "IGATE>APRS,WIDE2-1:}N0CALL>APRS,TCPIP,IGATE*:=4903.50N/07201.75W-Home";
+        let p = AprsPacket::parse(line).unwrap();
+        assert_eq!(p.source(), "IGATE");
+        assert_eq!(p.position(), None);
+        let inner = p.third_party().unwrap();
+        assert_eq!(inner.source(), "N0CALL");
+        assert_eq!(inner.path, vec!["TCPIP", "IGATE*"]);
+        assert_abs_diff_eq!(inner.latitude().unwrap(), 49.05833, epsilon = 0.00001);
+        assert_eq!(p.to_string(), line);
+
+        let nested = AprsPacket::parse("DIGI>APRS:}IGATE>APRS,TCPIP*:}N0CALL>APRS:>hello").unwrap();
+        assert_eq!(nested.innermost().source(), "N0CALL");
+        assert_eq!(nested.clone().into_innermost(), *nested.innermost());
+        assert_eq!(p.third_party().unwrap().innermost(), inner);
+
+        assert!(matches!(AprsPacket::parse("IGATE>APRS:}N0CALL:>hello"), Err(ParseError::InvalidThirdParty(_))));
+
+        // Nesting is capped rather than recursing without limit
+        let nested = |n: usize| format!("{}N0CALL>APRS:>hello", "A>B:}".repeat(n));
+        assert!(AprsPacket::parse(&nested(super::MAX_THIRD_PARTY_DEPTH)).is_ok());
+        assert!(matches!(AprsPacket::parse(&nested(super::MAX_THIRD_PARTY_DEPTH + 1)),
+            Err(ParseError::InvalidThirdParty(_))));
+        assert!(AprsPacket::parse(&nested(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_gate_to_rf() {
+        let p = AprsPacket::parse("N0CALL>APRS,TCPIP*,qAC,T2TEST:>hello").unwrap();
+        let p = p.gate_to_rf("IGATE", "APRS", vec!["WIDE2-1".to_string()]);
+        assert_eq!(p.to_string(), "IGATE>APRS,WIDE2-1:}N0CALL>APRS,TCPIP,IGATE*:>hello");
+        assert_eq!(p.into_innermost().source, "N0CALL");
+    }
+}
@@ -0,0 +1,176 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Degrees, Fahrenheits, Hectopascals, HundredthsOfInch, KilometersPerHour, MetersPerSecond, MilesPerHour,
+    ParseError, Percent, Weather};
+
+/// Raw weather station format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeetBrosFormat {
+    /// `$ULTW`, Ultimeter 2000 data logging mode.
+    Ultimeter,
+    /// `#`, Peet Bros U-II.
+    PeetBros,
+}
+
+/// Raw Peet Bros weather report, 16-bit hex fields as sent by the station.
+///
+/// Both formats start with gust, wind direction, temperature and long-term
+/// rain. Ultimeter reports go on with barometer, humidity, date, time,
+/// today's rain and average wind speed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeetBrosReport {
+    pub format: PeetBrosFormat,
+    /// Field values, `None` for `----`.
+    pub fields: Vec<Option<u16>>,
+}
+
+// Field indices
+const GUST: usize = 0;
+const DIRECTION: usize = 1;
+const TEMPERATURE: usize = 2;
+const RAIN_LONG_TERM: usize = 3;
+const PRESSURE: usize = 4;
+const HUMIDITY: usize = 8;
+const DAY_OF_YEAR: usize = 9;
+const MINUTE_OF_DAY: usize = 10;
+const RAIN_TODAY: usize = 11;
+const WIND_AVERAGE: usize = 12;
+
+impl PeetBrosReport {
+    fn field(&self, i: usize) -> Option<u16> {
+        self.fields.get(i).copied().flatten()
+    }
+
+    // Wind speed fields are in 0.1 kph
+    fn wind(&self, i: usize) -> Option<MilesPerHour> {
+        let v = KilometersPerHour(self.field(i)? as f32 / 10.0);
+        Some(MilesPerHour::from(MetersPerSecond::from(v)))
+    }
+
+    /// Weather data converted to the units of native weather reports.
+    pub fn weather(&self) -> Weather {
+        let ultimeter = self.format == PeetBrosFormat::Ultimeter;
+        let field = |i| if ultimeter || i <= RAIN_LONG_TERM { self.field(i) } else { None };
+        Weather {
+            wind_direction: self.field(DIRECTION).map(|v| Degrees((v & 0xff) as f32 * 360.0 / 256.0)),
+            wind_speed: if ultimeter { self.wind(WIND_AVERAGE) } else { None },
+            wind_gust: self.wind(GUST),
+            temperature: self.field(TEMPERATURE).map(|v| Fahrenheits(v as i16 as f32 / 10.0)),
+            rain_since_midnight: field(RAIN_TODAY).map(|v| HundredthsOfInch(v as f32)),
+            humidity: field(HUMIDITY).map(|v| Percent(v as f32 / 10.0)),
+            pressure: field(PRESSURE).map(|v| Hectopascals(v as f32 / 10.0)),
+            ..Weather::default()
+        }
+    }
+
+    /// Rain total since the counter was last reset.
+    pub fn rain_long_term(&self) -> Option<HundredthsOfInch> {
+        self.field(RAIN_LONG_TERM).map(|v| HundredthsOfInch(v as f32))
+    }
+
+    /// Station date and time as day of year and minute of day, Ultimeter only.
+    pub fn day_minute(&self) -> Option<(u16, u16)> {
+        match self.format {
+            PeetBrosFormat::Ultimeter => Some((self.field(DAY_OF_YEAR)?, self.field(MINUTE_OF_DAY)?)),
+            PeetBrosFormat::PeetBros => None,
+        }
+    }
+}
+
+impl FromStr for PeetBrosReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidPeetBros(s.to_string());
+        let (format, data) = if let Some(v) = s.strip_prefix("$ULTW") {
+            (PeetBrosFormat::Ultimeter, v)
+        } else if let Some(v) = s.strip_prefix('#') {
+            (PeetBrosFormat::PeetBros, v)
+        } else {
+            return Err(err());
+        };
+        let data = data.trim_end();
+        if data.is_empty() || data.len() % 4 != 0 || !data.is_ascii() {
+            return Err(err());
+        }
+        let fields = (0..data.len()).step_by(4)
+            .map(|i| match &data[i..i + 4] {
+                "----" => Ok(None),
+                v if v.bytes().all(|c| c.is_ascii_hexdigit()) => Ok(u16::from_str_radix(v, 16).ok()),
+                _ => Err(err()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(PeetBrosReport { format, fields })
+    }
+}
+
+impl Display for PeetBrosReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.format {
+            PeetBrosFormat::Ultimeter => write!(f, "$ULTW")?,
+            PeetBrosFormat::PeetBros => write!(f, "#")?,
+        }
+        for v in &self.fields {
+            match v {
+                Some(v) => write!(f, "{:04X}", v)?,
+                None => write!(f, "----")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Degrees, Fahrenheits, HundredthsOfInch, ParseError, PeetBrosFormat, PeetBrosReport, Percent};
+
+    #[test]
+    fn test_parse_ultimeter() {
+        let v: PeetBrosReport = "$ULTW0031003702CE0069----000086A00001----011901CC00000005".parse().unwrap();
+        assert_eq!(v.format, PeetBrosFormat::Ultimeter);
+        assert_eq!(v.fields.len(), 13);
+        let w = v.weather();
+        // 4.9 kph gust, 0x37 of 256 steps, 71.8°F
+        assert_abs_diff_eq!(w.wind_gust.unwrap().0, 3.045, epsilon = 0.001);
+        assert_abs_diff_eq!(w.wind_direction.unwrap().0, 77.34, epsilon = 0.01);
+        assert_abs_diff_eq!(w.temperature.unwrap().0, 71.8, epsilon = 0.001);
+        assert_eq!(w.pressure, None);
+        assert_eq!(w.humidity, None);
+        assert_eq!(w.rain_since_midnight, Some(HundredthsOfInch(0.0)));
+        assert_abs_diff_eq!(w.wind_speed.unwrap().0, 0.311, epsilon = 0.001);
+        assert_eq!(v.rain_long_term(), Some(HundredthsOfInch(105.0)));
+        assert_eq!(v.day_minute(), Some((0x119, 0x1cc)));
+    }
+
+    #[test]
+    fn test_parse_peet_bros() {
+        let v: PeetBrosReport = "#00000080FF9C0010".parse().unwrap();
+        assert_eq!(v.format, PeetBrosFormat::PeetBros);
+        let w = v.weather();
+        assert_eq!(w.wind_gust.map(|v| v.0), Some(0.0));
+        assert_eq!(w.wind_direction, Some(Degrees(180.0)));
+        assert_eq!(w.temperature, Some(Fahrenheits(-10.0)));
+        assert_eq!(v.rain_long_term(), Some(HundredthsOfInch(16.0)));
+        assert_eq!(v.day_minute(), None);
+
+        let v: PeetBrosReport = "$ULTW00000000----000027C1000000000000032001190000".parse().unwrap();
+        assert_eq!(v.weather().temperature, None);
+        assert_eq!(v.weather().humidity, Some(Percent(80.0)));
+        assert_abs_diff_eq!(v.weather().pressure.unwrap().0, 1017.7, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["$ULTW", "$ULTW003", "#00G0", "$GPRMC,123", "#"] {
+            assert_eq!(s.parse::<PeetBrosReport>(), Err(ParseError::InvalidPeetBros(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["$ULTW0031003702CE0069----000086A00001----011901CC00000005", "#00000080FF9C0010"] {
+            assert_eq!(s.parse::<PeetBrosReport>().unwrap().to_string(), s);
+        }
+    }
+}
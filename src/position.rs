@@ -0,0 +1,422 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Comment, CompressedExtension, CompressedPosition, CompressionType, Coverage, DataExtension, Degrees, Feet, Knots, Miles,
+    MetersPerSecond, MilesPerHour, ParseError, Position, Symbol, Telemetry, Timestamp, Weather};
+use crate::compressed::COMPRESSED_LEN;
+use crate::extension::EXTENSION_LEN;
+use crate::telemetry;
+
+/// Position report, data type identifiers `!`, `=`, `/` and `@`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionReport {
+    pub timestamp: Option<Timestamp>,
+    /// Station is capable of receiving messages (`=` and `@` reports).
+    pub messaging: bool,
+    pub position: Position,
+    pub symbol_table: u8,
+    pub symbol_code: u8,
+    /// Position was sent in the compressed format.
+    pub compressed: bool,
+    pub compressed_extension: Option<CompressedExtension>,
+    pub compression_type: Option<CompressionType>,
+    /// Data extension at the start of the comment, uncompressed positions only.
+    pub extension: Option<DataExtension>,
+    /// Weather data at the start of the comment of weather stations (`_` symbol).
+    /// Compressed reports carry the wind in the course/speed extension instead.
+    pub weather: Option<Weather>,
+    pub comment: String,
+}
+
+impl PositionReport {
+    pub fn symbol(&self) -> Symbol {
+        Symbol::from_table(self.symbol_table, self.symbol_code)
+    }
+
+    pub fn course(&self) -> Option<Degrees> {
+        match (self.compressed_extension, self.extension) {
+            (Some(CompressedExtension::CourseSpeed(v, _)), _) => Some(v),
+            // Zero course means unknown in the data extension
+            (_, Some(DataExtension::CourseSpeed { course, .. })) => course.filter(|v| v.0 != 0.0),
+            _ => None,
+        }
+    }
+
+    pub fn speed(&self) -> Option<Knots> {
+        match (self.compressed_extension, self.extension) {
+            (Some(CompressedExtension::CourseSpeed(_, v)), _) => Some(v),
+            (_, Some(DataExtension::CourseSpeed { speed, .. })) => speed,
+            _ => None,
+        }
+    }
+
+    /// Altitude from a compressed position or `/A=` in the comment.
+    pub fn altitude(&self) -> Option<Feet> {
+        match self.compressed_extension {
+            Some(CompressedExtension::Altitude(v)) => Some(v),
+            _ => Comment::parse(&self.comment).altitude,
+        }
+    }
+
+    /// Radio range from a compressed position, the `RNG` extension or estimated from `PHG`.
+    pub fn range(&self) -> Option<Miles> {
+        match (self.compressed_extension, self.extension) {
+            (Some(CompressedExtension::RadioRange(v)), _) => Some(v),
+            (_, Some(v)) => v.range(),
+            _ => None,
+        }
+    }
+
+    /// Area covered according to the range and antenna directivity.
+    pub fn coverage(&self) -> Option<Coverage> {
+        match (self.compressed_extension, self.extension) {
+            (Some(CompressedExtension::RadioRange(radius)), _) => Some(Coverage::Circle { center: self.position, radius }),
+            (_, Some(v)) => Coverage::from_extension(self.position, &v),
+            _ => None,
+        }
+    }
+
+    /// Base-91 telemetry embedded in the comment.
+    pub fn comment_telemetry(&self) -> Option<Telemetry> {
+        telemetry::split_comment(&self.comment).map(|v| v.0)
+    }
+
+    /// Weather data of weather station (`_` symbol) reports, with the wind
+    /// of compressed reports taken from the course/speed extension.
+    pub fn weather(&self) -> Option<Weather> {
+        if self.symbol_code != b'_' {
+            return None;
+        }
+        let mut weather = self.weather;
+        if let Some(CompressedExtension::CourseSpeed(course, speed)) = self.compressed_extension {
+            let v = weather.get_or_insert_with(Weather::default);
+            v.wind_direction = Some(course);
+            v.wind_speed = Some(MilesPerHour::from(MetersPerSecond::from(speed)));
+        }
+        weather
+    }
+
+    // Parses everything following the data type identifier and timestamp
+    pub(crate) fn parse_body(s: &str, timestamp: Option<Timestamp>, messaging: bool) -> Result<PositionReport, ParseError> {
+        let compressed = matches!(s.as_bytes().first(), Some(b'/' | b'\\' | b'A'..=b'Z' | b'a'..=b'j'));
+        if compressed {
+            let block = s.get(..COMPRESSED_LEN).ok_or_else(|| ParseError::InvalidCompressedPosition(s.to_string()))?;
+            let v: CompressedPosition = block.parse()?;
+            let (weather, comment) = match v.symbol_code {
+                b'_' => split_weather(&s[COMPRESSED_LEN..], Weather::parse_fields(&s[COMPRESSED_LEN..], false)),
+                _ => (None, &s[COMPRESSED_LEN..]),
+            };
+            return Ok(PositionReport {
+                timestamp,
+                messaging,
+                position: v.position,
+                symbol_table: v.symbol_table,
+                symbol_code: v.symbol_code,
+                compressed: true,
+                compressed_extension: v.extension,
+                compression_type: v.compression_type,
+                extension: None,
+                weather,
+                comment: comment.to_string(),
+            });
+        }
+
+        let (position, symbol_table, symbol_code) = parse_uncompressed(s)?;
+        let mut comment = &s[UNCOMPRESSED_LEN..];
+        // Weather stations put the wind where the course and speed would be
+        let mut weather = None;
+        let extension = match (symbol_table, symbol_code) {
+            (_, b'_') => {
+                (weather, comment) = split_weather(comment, Weather::parse_after_symbol(comment));
+                None
+            }
+            (b'\\', b'l') => DataExtension::parse(comment, true),
+            _ => DataExtension::parse(comment, false),
+        };
+        if extension.is_some() {
+            comment = &comment[EXTENSION_LEN..];
+        }
+        Ok(PositionReport {
+            timestamp,
+            messaging,
+            position,
+            symbol_table,
+            symbol_code,
+            compressed: false,
+            compressed_extension: None,
+            compression_type: None,
+            extension,
+            weather,
+            comment: comment.to_string(),
+        })
+    }
+
+    pub(crate) fn fmt_body(&self, f: &mut Formatter) -> fmt::Result {
+        if self.compressed {
+            let v = CompressedPosition {
+                position: self.position,
+                symbol_table: self.symbol_table,
+                symbol_code: self.symbol_code,
+                extension: self.compressed_extension,
+                compression_type: self.compression_type,
+            };
+            write!(f, "{}", v)?;
+            if let Some(v) = self.weather {
+                v.fmt_fields(f)?;
+            }
+            return write!(f, "{}", self.comment);
+        }
+
+        let ambiguity = ambiguity_from_precision(self.position.precision);
+        write!(f, "{}{}{}{}",
+            encode_coordinate(self.position.latitude, 2, ['N', 'S'], ambiguity),
+            self.symbol_table as char,
+            encode_coordinate(self.position.longitude, 3, ['E', 'W'], ambiguity),
+            self.symbol_code as char)?;
+        if let Some(v) = self.extension {
+            write!(f, "{}", v)?;
+        }
+        if let Some(v) = self.weather {
+            v.fmt_after_symbol(f)?;
+        }
+        write!(f, "{}", self.comment)
+    }
+}
+
+impl FromStr for PositionReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (messaging, timestamped) = match s.as_bytes().first() {
+            Some(b'!') => (false, false),
+            Some(b'=') => (true, false),
+            Some(b'/') => (false, true),
+            Some(b'@') => (true, true),
+            _ => return Err(ParseError::InvalidPosition(s.to_string())),
+        };
+        let body = &s[1..];
+        if timestamped {
+            let ts = body.get(..7).ok_or_else(|| ParseError::InvalidTimestamp(body.to_string()))?;
+            PositionReport::parse_body(&body[7..], Some(Timestamp::parse(ts)?), messaging)
+        } else {
+            PositionReport::parse_body(body, None, messaging)
+        }
+    }
+}
+
+impl Display for PositionReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match (self.timestamp, self.messaging) {
+            (None, false) => write!(f, "!")?,
+            (None, true) => write!(f, "=")?,
+            (Some(ts), false) => write!(f, "/{}", ts)?,
+            (Some(ts), true) => write!(f, "@{}", ts)?,
+        }
+        self.fmt_body(f)
+    }
+}
+
+// Weather parsed from the start of `comment`, `None` if nothing was recognised
+fn split_weather<'a>(comment: &str, (weather, rest): (Weather, &'a str)) -> (Option<Weather>, &'a str) {
+    if rest.len() == comment.len() { (None, rest) } else { (Some(weather), rest) }
+}
+
+// `ddmm.hhN/dddmm.hhW>`
+const UNCOMPRESSED_LEN: usize = 19;
+
+// Size of the ambiguity box for 1 to 4 blanked out digits
+pub(crate) const AMBIGUITY_FEET: [f32; 4] = [607.6, 6076.1, 60761.2, 364567.2];
+
+pub(crate) fn ambiguity_from_precision(precision: Option<Feet>) -> usize {
+    precision.map_or(0, |p| AMBIGUITY_FEET.iter().filter(|&&v| p.0 >= v * 0.99).count())
+}
+
+fn parse_uncompressed(s: &str) -> Result<(Position, u8, u8), ParseError> {
+    let err = || ParseError::InvalidPosition(s.to_string());
+    let b = s.as_bytes();
+    if b.len() < UNCOMPRESSED_LEN || !s.is_char_boundary(UNCOMPRESSED_LEN) {
+        return Err(err());
+    }
+    let (lat, ambiguity) = parse_coordinate(&b[0..8], 2, [b'N', b'S'], 90.0).ok_or_else(err)?;
+    let (lng, _) = parse_coordinate(&b[9..18], 3, [b'E', b'W'], 180.0).ok_or_else(err)?;
+    let table = b[8];
+    if !(table == b'/' || table == b'\\' || table.is_ascii_digit() || table.is_ascii_uppercase()) {
+        return Err(err());
+    }
+
+    let mut position = Position::from_latlng(lat, lng);
+    if ambiguity > 0 {
+        position.set_precision(Feet(AMBIGUITY_FEET[ambiguity - 1]));
+    }
+    Ok((position, table, b[18]))
+}
+
+// Parses `ddmm.hhN` or `dddmm.hhE`, returns the value and the number of
+// digits blanked out for position ambiguity
+fn parse_coordinate(b: &[u8], deg_len: usize, hemispheres: [u8; 2], max: f32) -> Option<(f32, usize)> {
+    let (digits, hemisphere) = b.split_at(b.len() - 1);
+    if digits[deg_len + 2] != b'.' {
+        return None;
+    }
+    // Only minutes can be blanked out, starting from the rightmost digit
+    let significant: Vec<u8> = digits.iter().copied().filter(|&c| c != b'.').collect();
+    let ambiguity = significant.iter().rev().take_while(|&&c| c == b' ').count();
+    let (known, _) = significant.split_at(significant.len() - ambiguity);
+    if ambiguity > 4 || !known.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let number = |v: &[u8]| v.iter().fold(0, |acc, &c| acc * 10 + if c == b' ' { 0 } else { (c - b'0') as u32 });
+
+    let deg = number(&significant[..deg_len]);
+    let hundredths = number(&significant[deg_len..]);
+    let v = deg as f32 + hundredths as f32 / 6000.0;
+    if hundredths >= 6000 || v > max {
+        return None;
+    }
+    match hemisphere[0] {
+        h if h == hemispheres[0] => Some((v, ambiguity)),
+        h if h == hemispheres[1] => Some((-v, ambiguity)),
+        _ => None,
+    }
+}
+
+fn encode_coordinate(v: f32, deg_len: usize, hemispheres: [char; 2], ambiguity: usize) -> String {
+    let hundredths = (v.abs() as f64 * 6000.0).round() as u32;
+    let mut s = format!("{:0w$}{:02}.{:02}", hundredths / 6000, hundredths % 6000 / 100, hundredths % 100, w = deg_len);
+    for _ in 0..ambiguity.min(4) {
+        let i = s.rfind(|c: char| c.is_ascii_digit()).unwrap_or(0);
+        s.replace_range(i..i + 1, " ");
+    }
+    s.push(if v < 0.0 { hemispheres[1] } else { hemispheres[0] });
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DataExtension, Degrees, Fahrenheits, Feet, Knots, Miles, MilesPerHour, ParseError, PositionReport,
+        Symbol, Timestamp, Watts};
+
+    #[test]
+    fn test_parse_plain() {
+        let r: PositionReport = "!4903.50N/07201.75W-Test 001234".parse().unwrap();
+        assert_abs_diff_eq!(r.position.latitude, 49.05833, epsilon = 0.00001);
+        assert_abs_diff_eq!(r.position.longitude, -72.02917, epsilon = 0.00001);
+        assert_eq!(r.position.precision, None);
+        assert_eq!(r.symbol(), Symbol::HouseQth);
+        assert_eq!(r.comment, "Test 001234");
+        assert_eq!(r.timestamp, None);
+        assert!(!r.messaging);
+    }
+
+    #[test]
+    fn test_parse_data_types() {
+        let r: PositionReport = "=4903.50S\\07201.75E>".parse().unwrap();
+        assert!(r.messaging);
+        assert!(r.position.latitude < 0.0 && r.position.longitude > 0.0);
+        assert_eq!(r.symbol(), Symbol::OverlayedVehicles);
+
+        let r: PositionReport = "/092345z4903.50N/07201.75W>".parse().unwrap();
+        assert!(!r.messaging);
+        assert_eq!(r.timestamp, Some(Timestamp::DayHourMinute { day: 9, hour: 23, minute: 45, zulu: true }));
+
+        let r: PositionReport = "@234517h4903.50N/07201.75W>".parse().unwrap();
+        assert!(r.messaging);
+        assert_eq!(r.timestamp, Some(Timestamp::HourMinuteSecond { hour: 23, minute: 45, second: 17 }));
+    }
+
+    #[test]
+    fn test_parse_compressed() {
+        let r: PositionReport = "=/5L!!<*e7>7P[Moving".parse().unwrap();
+        assert!(r.compressed);
+        assert!(r.messaging);
+        assert_abs_diff_eq!(r.position.latitude, 49.5, epsilon = 0.0001);
+        assert_eq!(r.symbol(), Symbol::Car);
+        assert_eq!(r.course(), Some(Degrees(88.0)));
+        assert_abs_diff_eq!(r.speed().unwrap().0, 36.2, epsilon = 0.1);
+        assert_eq!(r.comment, "Moving");
+
+        let r: PositionReport = "@092345z/5L!!<*e7OS]S".parse().unwrap();
+        assert_abs_diff_eq!(r.altitude().unwrap().0, 10004.0, epsilon = 1.0);
+        assert_eq!(r.course(), None);
+        assert!("!/5L!!<*e7>7P".parse::<PositionReport>().is_err());
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        let r: PositionReport = "!4903.50N/07201.75W>088/036/A=001234".parse().unwrap();
+        assert_eq!(r.course(), Some(Degrees(88.0)));
+        assert_eq!(r.speed(), Some(Knots(36.0)));
+        assert_eq!(r.comment, "/A=001234");
+        assert_eq!(r.altitude(), Some(Feet(1234.0)));
+
+        let r: PositionReport = "=4903.50N/07201.75W#PHG5132Digi".parse().unwrap();
+        assert!(matches!(r.extension, Some(DataExtension::PowerHeightGain { power: Watts(25.0), .. })));
+        assert_eq!(r.comment, "Digi");
+
+        let r: PositionReport = "!4903.50N/07201.75W#RNG0050".parse().unwrap();
+        assert_eq!(r.range(), Some(Miles(50.0)));
+
+        let r: PositionReport = "!4903.50N\\07201.75Wl435/305Lake".parse().unwrap();
+        assert!(matches!(r.extension, Some(DataExtension::Area { shape: 4, color: 3, .. })));
+
+        let r: PositionReport = "!4903.50N/07201.75W>000/000".parse().unwrap();
+        assert_eq!(r.course(), None);
+        assert_eq!(r.speed(), Some(Knots(0.0)));
+        let r: PositionReport = "!4903.50N/07201.75W>08/036".parse().unwrap();
+        assert_eq!(r.extension, None);
+    }
+
+    #[test]
+    fn test_weather() {
+        let r: PositionReport = "@092345z4903.50N/07201.75W_220/004g005t077r000p000P000h50b09900wRSW".parse().unwrap();
+        let w = r.weather().unwrap();
+        assert_eq!(w.wind_direction, Some(Degrees(220.0)));
+        assert_eq!(w.wind_speed, Some(MilesPerHour(4.0)));
+        assert_eq!(w.temperature, Some(Fahrenheits(77.0)));
+        assert_eq!(r.comment, "wRSW");
+
+        let r: PositionReport = "=/5L!!<*e7_7P[g008t054".parse().unwrap();
+        let w = r.weather().unwrap();
+        assert_eq!(w.wind_direction, Some(Degrees(88.0)));
+        assert_abs_diff_eq!(w.wind_speed.unwrap().0, 41.7, epsilon = 0.1);
+        assert_eq!(w.temperature, Some(Fahrenheits(54.0)));
+        assert_eq!(r.comment, "");
+
+        let r: PositionReport = "!4903.50N/07201.75W_Weather station".parse().unwrap();
+        assert_eq!(r.weather(), None);
+        assert_eq!(r.comment, "Weather station");
+
+        let r: PositionReport = "!4903.50N/07201.75W-220/004g005t077".parse().unwrap();
+        assert_eq!(r.weather(), None);
+    }
+
+    #[test]
+    fn test_parse_ambiguity() {
+        let r: PositionReport = "!4903.5 N/07201.7 W-".parse().unwrap();
+        assert_eq!(r.position.precision, Some(Feet(607.6)));
+        let r: PositionReport = "!49  .  N/072  .  W-".parse().unwrap();
+        assert_eq!(r.position.precision, Some(Feet(364567.2)));
+        assert_eq!(r.position.latitude, 49.0);
+        assert!("!490 .5 N/07201.7 W-".parse::<PositionReport>().is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["!4903.50N/07201.75", "!9103.50N/07201.75W-", "!4963.50N/07201.75W-",
+                  "!4903.50X/07201.75W-", "!4903.50N/07201.75Q-", "!4903.50Nx07201.75W-"] {
+            assert_eq!(s.parse::<PositionReport>(), Err(ParseError::InvalidPosition(s[1..].to_string())));
+        }
+        assert!("/0923xx4903.50N/07201.75W>".parse::<PositionReport>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for s in ["!4903.50N/07201.75W-Test", "=0003.50S\\00001.75E>", "@092345z4903.50N/07201.75W>",
+                  "/234517h4903.5 N/07201.7 W-", "!49  .  N/072  .  W-", "=/5L!!<*e6>7P[Moving",
+                  "@092345z/5L!!<*e6OS]S", "!\\5L!!<*e6> sT", "!4903.50N/07201.75W>088/036/A=001234",
+                  "=4903.50N/07201.75W#PHG5132Digi", "!4903.50N\\07201.75Wl435/305",
+                  "@092345z4903.50N/07201.75W_220/004g005t077r000p000P000h50b09900wRSW", "=/5L!!<*e6_7P[g008t054"] {
+            assert_eq!(s.parse::<PositionReport>().unwrap().to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Message, MessageKind, Miles, ParseError, Position};
+
+/// What a general query asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryType {
+    /// `?APRS?`, all stations.
+    Aprs,
+    /// `?IGATE?`, IGates, which answer with their capabilities.
+    IGate,
+    /// `?WX?`, weather stations.
+    Weather,
+    /// Any other `?NAME?` query.
+    Other(String),
+}
+
+impl QueryType {
+    fn from_name(name: &str) -> QueryType {
+        match name {
+            "APRS" => QueryType::Aprs,
+            "IGATE" => QueryType::IGate,
+            "WX" => QueryType::Weather,
+            v => QueryType::Other(v.to_string()),
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            QueryType::Aprs => "APRS",
+            QueryType::IGate => "IGATE",
+            QueryType::Weather => "WX",
+            QueryType::Other(v) => v,
+        }
+    }
+}
+
+/// Area a general query is restricted to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryFootprint {
+    pub position: Position,
+    pub radius: Miles,
+}
+
+/// General query, data type identifier `?`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub query: QueryType,
+    /// Only stations within the footprint should respond.
+    pub footprint: Option<QueryFootprint>,
+}
+
+fn parse_footprint(s: &str) -> Option<QueryFootprint> {
+    let mut parts = s.split(',').map(str::trim);
+    let lat = parts.next()?.parse().ok()?;
+    let lng = parts.next()?.parse().ok()?;
+    let radius = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(QueryFootprint { position: Position::from_latlng(lat, lng), radius: Miles(radius) })
+}
+
+impl FromStr for Query {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidQuery(s.to_string());
+        let body = s.strip_prefix('?').ok_or_else(err)?.trim_end_matches(['\r', '\n']);
+        let (name, rest) = body.split_once('?').ok_or_else(err)?;
+        if name.is_empty() || !name.bytes().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Err(err());
+        }
+        let footprint = match rest.trim() {
+            "" => None,
+            v => Some(parse_footprint(v).ok_or_else(err)?),
+        };
+        Ok(Query { query: QueryType::from_name(name), footprint })
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "?{}?", self.query.name())?;
+        if let Some(v) = self.footprint {
+            write!(f, " {},{},{:04}", v.position.latitude, v.position.longitude, v.radius.0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Query sent in a message to a single station.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectedQuery {
+    /// `?APRSD`, stations heard direct.
+    Direct,
+    /// `?APRSH call`, how often the station heard `call`.
+    Heard(String),
+    /// `?APRSM`, messages pending for the sender.
+    Messages,
+    /// `?APRSO`, objects the station transmits.
+    Objects,
+    /// `?APRSP`, station position.
+    Position,
+    /// `?APRSS`, station status.
+    Status,
+    /// `?APRST`, route trace.
+    Trace,
+    /// `?PING?`, route trace.
+    Ping,
+    /// General query addressed to a single station, such as `?IGATE?`.
+    General(QueryType),
+}
+
+impl DirectedQuery {
+    /// Message carrying the query to `addressee`.
+    pub fn to_message(&self, addressee: &str) -> Message {
+        Message {
+            addressee: addressee.to_string(),
+            kind: MessageKind::Text { text: self.to_string(), id: None, reply_ack: None },
+        }
+    }
+}
+
+impl FromStr for DirectedQuery {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidQuery(s.to_string());
+        let body = s.trim_end();
+        match body {
+            "?APRSD" => Ok(DirectedQuery::Direct),
+            "?APRSM" => Ok(DirectedQuery::Messages),
+            "?APRSO" => Ok(DirectedQuery::Objects),
+            "?APRSP" => Ok(DirectedQuery::Position),
+            "?APRSS" => Ok(DirectedQuery::Status),
+            "?APRST" => Ok(DirectedQuery::Trace),
+            "?PING?" => Ok(DirectedQuery::Ping),
+            v if v.starts_with("?APRSH") => match v[6..].trim() {
+                "" => Err(err()),
+                call => Ok(DirectedQuery::Heard(call.to_string())),
+            },
+            v => Ok(DirectedQuery::General(v.parse::<Query>().map_err(|_| err())?.query)),
+        }
+    }
+}
+
+impl Display for DirectedQuery {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            DirectedQuery::Direct => write!(f, "?APRSD"),
+            DirectedQuery::Heard(v) => write!(f, "?APRSH {}", v),
+            DirectedQuery::Messages => write!(f, "?APRSM"),
+            DirectedQuery::Objects => write!(f, "?APRSO"),
+            DirectedQuery::Position => write!(f, "?APRSP"),
+            DirectedQuery::Status => write!(f, "?APRSS"),
+            DirectedQuery::Trace => write!(f, "?APRST"),
+            DirectedQuery::Ping => write!(f, "?PING?"),
+            DirectedQuery::General(v) => write!(f, "?{}?", v.name()),
+        }
+    }
+}
+
+/// Station capabilities, data type identifier `<`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Tokens in the order sent, with their value if given as `TOKEN=value`.
+    pub entries: Vec<(String, Option<String>)>,
+}
+
+impl Capabilities {
+    pub fn contains(&self, token: &str) -> bool {
+        self.entries.iter().any(|(k, _)| k == token)
+    }
+
+    /// Value of a `TOKEN=value` entry.
+    pub fn get(&self, token: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == token).and_then(|(_, v)| v.as_deref())
+    }
+}
+
+impl FromStr for Capabilities {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidCapabilities(s.to_string());
+        let body = s.strip_prefix('<').ok_or_else(err)?.trim_end_matches(['\r', '\n']);
+        let entries = body.split(',')
+            .map(|v| match v.split_once('=') {
+                Some((k, v)) => (k.trim().to_string(), Some(v.trim().to_string())),
+                None => (v.trim().to_string(), None),
+            })
+            .collect::<Vec<_>>();
+        if entries.iter().any(|(k, _)| k.is_empty()) {
+            return Err(err());
+        }
+        Ok(Capabilities { entries })
+    }
+}
+
+impl Display for Capabilities {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "<")?;
+        for (i, (k, v)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            match v {
+                Some(v) => write!(f, "{}={}", k, v)?,
+                None => write!(f, "{}", k)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Capabilities, DirectedQuery, Miles, ParseError, Query, QueryType};
+
+    #[test]
+    fn test_parse_query() {
+        let v: Query = "?APRS?".parse().unwrap();
+        assert_eq!(v, Query { query: QueryType::Aprs, footprint: None });
+        assert_eq!("?IGATE?".parse::<Query>().unwrap().query, QueryType::IGate);
+        assert_eq!("?WX?".parse::<Query>().unwrap().query, QueryType::Weather);
+        assert_eq!("?DGPS?".parse::<Query>().unwrap().query, QueryType::Other("DGPS".to_string()));
+
+        let v: Query = "?APRS? 34.02,-117.15,0200".parse().unwrap();
+        let footprint = v.footprint.unwrap();
+        assert_abs_diff_eq!(footprint.position.latitude, 34.02, epsilon = 0.0001);
+        assert_abs_diff_eq!(footprint.position.longitude, -117.15, epsilon = 0.0001);
+        assert_eq!(footprint.radius, Miles(200.0));
+
+        for s in ["APRS?", "?APRS", "??", "?aprs?", "?APRS? 34.02,-117.15"] {
+            assert_eq!(s.parse::<Query>(), Err(ParseError::InvalidQuery(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_parse_directed_query() {
+        assert_eq!("?APRSP".parse(), Ok(DirectedQuery::Position));
+        assert_eq!("?APRSS".parse(), Ok(DirectedQuery::Status));
+        assert_eq!("?PING?".parse(), Ok(DirectedQuery::Ping));
+        assert_eq!("?APRSH N0CALL".parse(), Ok(DirectedQuery::Heard("N0CALL".to_string())));
+        assert_eq!("?IGATE?".parse(), Ok(DirectedQuery::General(QueryType::IGate)));
+        assert!("?APRSX".parse::<DirectedQuery>().is_err());
+        assert!("?APRSH".parse::<DirectedQuery>().is_err());
+        assert_eq!(DirectedQuery::Status.to_message("KH2Z").to_string(), ":KH2Z     :?APRSS");
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let v: Capabilities = "<IGATE,MSG_CNT=30,LOC_CNT=10".parse().unwrap();
+        assert!(v.contains("IGATE"));
+        assert_eq!(v.get("IGATE"), None);
+        assert_eq!(v.get("MSG_CNT"), Some("30"));
+        assert_eq!(v.get("LOC_CNT"), Some("10"));
+        assert!(!v.contains("WX"));
+        assert!("<IGATE,,MSG_CNT=3".parse::<Capabilities>().is_err());
+        assert!("IGATE".parse::<Capabilities>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["?APRS?", "?WX?", "?DGPS?", "?APRS? 34.02,-117.15,0200"] {
+            assert_eq!(s.parse::<Query>().unwrap().to_string(), s);
+        }
+        for s in ["?APRSD", "?APRSH N0CALL", "?APRSM", "?APRSO", "?APRSP", "?APRSS", "?APRST", "?PING?", "?WX?"] {
+            assert_eq!(s.parse::<DirectedQuery>().unwrap().to_string(), s);
+        }
+        assert_eq!("<IGATE,MSG_CNT=30,LOC_CNT=10".parse::<Capabilities>().unwrap().to_string(),
+            "<IGATE,MSG_CNT=30,LOC_CNT=10");
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::{AprsData, AprsPacket, Message, MessageKind, ReplyAck};
+
+// Two-character IDs keep messages compatible with reply-ack peers
+const ID_SPACE: u32 = 36 * 36;
+const ID_DIGITS: &[u8; 36] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Peers whose last message ID is kept for a reply-ack
+const MAX_REPLY_ACKS: usize = 64;
+
+/// Source of time for a [`MessageSession`].
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// [`Clock`] backed by [`Instant::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+}
+
+/// Sink for packets transmitted by a [`MessageSession`].
+pub trait Transport {
+    fn send(&mut self, packet: &AprsPacket);
+}
+
+/// Retransmission schedule for unacknowledged messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Delay before the first retransmission.
+    pub initial_interval: Duration,
+    /// Factor applied to the delay after each retransmission. Values that
+    /// don't give a valid delay, such as negative ones, jump to `max_interval`.
+    pub multiplier: f32,
+    /// Upper bound of the delay.
+    pub max_interval: Duration,
+    /// Transmissions, including the first one, before giving up.
+    pub max_attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            initial_interval: Duration::from_secs(30),
+            multiplier: 2.0,
+            max_interval: Duration::from_secs(600),
+            max_attempts: 5,
+        }
+    }
+}
+
+/// Outcome of an outgoing message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionEvent {
+    /// Addressee acknowledged the message, possibly with a reply-ack.
+    Delivered { addressee: String, id: String },
+    /// Addressee rejected the message.
+    Rejected { addressee: String, id: String },
+    /// No answer after all attempts of the [`RetryPolicy`].
+    TimedOut { addressee: String, id: String },
+}
+
+#[derive(Debug)]
+struct Outgoing {
+    message: Message,
+    attempts: u32,
+    interval: Duration,
+    // `None` when the next attempt is too far out to represent, the
+    // message times out instead
+    next_attempt: Option<Instant>,
+}
+
+/// Reliable message delivery for a single station.
+///
+/// Outgoing messages are assigned IDs and retransmitted per [`RetryPolicy`]
+/// until acknowledged or rejected. Incoming messages addressed to the station
+/// are acknowledged automatically and their IDs are piggy-backed as reply-acks
+/// on later messages to peers that support them.
+pub struct MessageSession<C: Clock, T: Transport> {
+    callsign: String,
+    destination: String,
+    path: Vec<String>,
+    policy: RetryPolicy,
+    clock: C,
+    transport: T,
+    next_id: u32,
+    outgoing: Vec<Outgoing>,
+    // Last ID received from the most recent reply-ack capable peers, dropped
+    // once sent back
+    reply_acks: VecDeque<(String, String)>,
+}
+
+impl<C: Clock, T: Transport> MessageSession<C, T> {
+    pub fn new(callsign: &str, policy: RetryPolicy, clock: C, transport: T) -> Self {
+        MessageSession {
+            callsign: callsign.to_string(),
+            destination: "APRS".to_string(),
+            path: vec![],
+            policy,
+            clock,
+            transport,
+            next_id: 1,
+            outgoing: vec![],
+            reply_acks: VecDeque::new(),
+        }
+    }
+
+    /// Sets the destination and digipeater path of transmitted packets.
+    pub fn set_route(&mut self, destination: &str, path: &[&str]) -> &mut Self {
+        self.destination = destination.to_string();
+        self.path = path.iter().map(|v| v.to_string()).collect();
+        self
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
+    /// Number of messages awaiting an answer.
+    pub fn pending(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Transmits `text` to `addressee` and returns the assigned message ID.
+    pub fn send(&mut self, addressee: &str, text: &str) -> String {
+        let id = self.assign_id();
+        let reply_ack = self.reply_acks.iter().position(|(peer, _)| peer == addressee)
+            .and_then(|i| self.reply_acks.remove(i))
+            .map(|(_, id)| ReplyAck::Ack(id));
+        let message = Message {
+            addressee: addressee.to_string(),
+            kind: MessageKind::Text {
+                text: text.to_string(),
+                id: Some(id.clone()),
+                reply_ack: Some(reply_ack.unwrap_or(ReplyAck::Supported)),
+            },
+        };
+        self.transmit(message.clone());
+        self.outgoing.push(Outgoing {
+            message,
+            attempts: 1,
+            interval: self.policy.initial_interval,
+            next_attempt: self.clock.now().checked_add(self.policy.initial_interval),
+        });
+        id
+    }
+
+    /// Handles a received packet, acking messages addressed to this station.
+    /// Third-party packets are matched against the station that originated them.
+    pub fn receive(&mut self, packet: &AprsPacket) -> Vec<SessionEvent> {
+        let packet = packet.innermost();
+        let message = match &packet.data {
+            AprsData::Message(v) if v.addressee.eq_ignore_ascii_case(&self.callsign) => v,
+            _ => return vec![],
+        };
+        let source = &packet.source;
+        let mut events = vec![];
+        if let Some(id) = message.reply_ack_id() {
+            events.extend(self.complete(source, id, false));
+        }
+        match &message.kind {
+            MessageKind::Ack { id, .. } => events.extend(self.complete(source, id, false)),
+            MessageKind::Reject { id, .. } => events.extend(self.complete(source, id, true)),
+            MessageKind::Text { id: Some(id), reply_ack, .. } => {
+                if reply_ack.is_some() {
+                    self.reply_acks.retain(|(peer, _)| peer != source);
+                    if self.reply_acks.len() >= MAX_REPLY_ACKS {
+                        self.reply_acks.pop_front();
+                    }
+                    self.reply_acks.push_back((source.clone(), id.clone()));
+                }
+                self.transmit(Message {
+                    addressee: source.clone(),
+                    kind: MessageKind::Ack { id: id.clone(), reply_ack: None },
+                });
+            }
+            MessageKind::Text { id: None, .. } => {}
+        }
+        events
+    }
+
+    /// Retransmits due messages and expires the ones out of attempts.
+    pub fn poll(&mut self) -> Vec<SessionEvent> {
+        let now = self.clock.now();
+        let mut events = vec![];
+        let mut retransmit = vec![];
+        let policy = self.policy;
+        self.outgoing.retain_mut(|v| {
+            if v.next_attempt.is_some_and(|t| t > now) {
+                return true;
+            }
+            v.interval = Duration::try_from_secs_f32(v.interval.as_secs_f32() * policy.multiplier)
+                .map_or(policy.max_interval, |v| v.min(policy.max_interval));
+            v.next_attempt = v.next_attempt.and_then(|_| now.checked_add(v.interval));
+            if v.attempts >= policy.max_attempts || v.next_attempt.is_none() {
+                events.push(SessionEvent::TimedOut {
+                    addressee: v.message.addressee.clone(),
+                    id: v.message.id().unwrap_or_default().to_string(),
+                });
+                return false;
+            }
+            v.attempts += 1;
+            retransmit.push(v.message.clone());
+            true
+        });
+        for v in retransmit {
+            self.transmit(v);
+        }
+        events
+    }
+
+    // Skips IDs still in flight, unless every one of them is
+    fn assign_id(&mut self) -> String {
+        let mut id = String::new();
+        for _ in 0..ID_SPACE {
+            let n = self.next_id;
+            self.next_id = (n + 1) % ID_SPACE;
+            id = [ID_DIGITS[(n / 36) as usize] as char, ID_DIGITS[(n % 36) as usize] as char].iter().collect();
+            if !self.outgoing.iter().any(|v| v.message.id() == Some(&id)) {
+                break;
+            }
+        }
+        id
+    }
+
+    fn complete(&mut self, source: &str, id: &str, rejected: bool) -> Option<SessionEvent> {
+        let index = self.outgoing.iter().position(|v| {
+            v.message.addressee.eq_ignore_ascii_case(source) && v.message.id() == Some(id)
+        })?;
+        let v = self.outgoing.remove(index);
+        let (addressee, id) = (v.message.addressee, id.to_string());
+        Some(if rejected {
+            SessionEvent::Rejected { addressee, id }
+        } else {
+            SessionEvent::Delivered { addressee, id }
+        })
+    }
+
+    fn transmit(&mut self, message: Message) {
+        self.transport.send(&AprsPacket {
+            source: self.callsign.clone(),
+            destination: self.destination.clone(),
+            path: self.path.clone(),
+            data: AprsData::Message(message),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::{Duration, Instant};
+
+    use crate::{AprsPacket, Clock, MessageSession, RetryPolicy, SessionEvent, Transport};
+
+    struct TestClock(Cell<Instant>);
+
+    impl TestClock {
+        fn advance(&self, secs: u64) {
+            self.0.set(self.0.get() + Duration::from_secs(secs));
+        }
+    }
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    impl Transport for Vec<String> {
+        fn send(&mut self, packet: &AprsPacket) {
+            self.push(packet.to_string());
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_interval: Duration::from_secs(10),
+            multiplier: 2.0,
+            max_interval: Duration::from_secs(30),
+            max_attempts: 4,
+        }
+    }
+
+    fn receive<C: Clock>(session: &mut MessageSession<C, Vec<String>>, line: &str) -> Vec<SessionEvent> {
+        session.receive(&line.parse().unwrap())
+    }
+
+    #[test]
+    fn test_retry_and_timeout() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        session.set_route("APRS", &["WIDE2-1"]);
+        assert_eq!(session.send("WU2Z", "Hello"), "01");
+        assert_eq!(session.transport(), &["N0CALL>APRS,WIDE2-1::WU2Z     :Hello{01}"]);
+
+        // Retransmits after 10, 20 and 30 (capped) seconds
+        for (secs, sent) in [(9, 1), (1, 2), (19, 2), (1, 3), (30, 4)] {
+            clock.advance(secs);
+            assert!(session.poll().is_empty());
+            assert_eq!(session.transport().len(), sent);
+        }
+        clock.advance(30);
+        let events = session.poll();
+        assert_eq!(events, [SessionEvent::TimedOut { addressee: "WU2Z".to_string(), id: "01".to_string() }]);
+        assert_eq!(session.pending(), 0);
+        assert_eq!(session.transport().len(), 4);
+    }
+
+    #[test]
+    fn test_ack_and_reject() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        let first = session.send("WU2Z", "One");
+        let second = session.send("WU2Z", "Two");
+        assert_eq!(session.pending(), 2);
+
+        assert!(receive(&mut session, "WU2Z>APRS::N0CALL   :ack99").is_empty());
+        assert!(receive(&mut session, "KB2ICI>APRS::N0CALL   :ack01").is_empty());
+        assert_eq!(receive(&mut session, "WU2Z>APRS::N0CALL   :ack01"),
+            [SessionEvent::Delivered { addressee: "WU2Z".to_string(), id: first }]);
+        assert_eq!(receive(&mut session, "WU2Z>APRS::N0CALL   :rej02"),
+            [SessionEvent::Rejected { addressee: "WU2Z".to_string(), id: second }]);
+        assert_eq!(session.pending(), 0);
+
+        clock.advance(60);
+        assert!(session.poll().is_empty());
+        assert_eq!(session.transport().len(), 2);
+    }
+
+    #[test]
+    fn test_reply_ack() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        session.send("WU2Z", "Ping");
+
+        // Incoming message carries a reply-ack for ours and gets acked in turn
+        assert_eq!(receive(&mut session, "WU2Z>APRS::N0CALL   :Pong{QX}01"),
+            [SessionEvent::Delivered { addressee: "WU2Z".to_string(), id: "01".to_string() }]);
+        assert_eq!(session.transport()[1], "N0CALL>APRS::WU2Z     :ackQX");
+
+        session.send("WU2Z", "Again");
+        assert_eq!(session.transport()[2], "N0CALL>APRS::WU2Z     :Again{02}QX");
+    }
+
+    #[test]
+    fn test_invalid_multiplier() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        for multiplier in [-1.0, f32::NAN, f32::MAX] {
+            let mut session = MessageSession::new("N0CALL", RetryPolicy { multiplier, ..policy() }, &clock, vec![]);
+            session.send("WU2Z", "Hello");
+            clock.advance(10);
+            assert!(session.poll().is_empty());
+            // Next retransmission waits for the maximum interval
+            clock.advance(29);
+            session.poll();
+            assert_eq!(session.transport().len(), 2);
+            clock.advance(1);
+            session.poll();
+            assert_eq!(session.transport().len(), 3);
+        }
+    }
+
+    #[test]
+    fn test_interval_overflow() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let timed_out = [SessionEvent::TimedOut { addressee: "WU2Z".to_string(), id: "01".to_string() }];
+        let long = RetryPolicy { initial_interval: Duration::MAX, ..policy() };
+        let mut session = MessageSession::new("N0CALL", long, &clock, vec![]);
+        session.send("WU2Z", "Hello");
+        assert_eq!(session.poll(), timed_out);
+
+        let growing = RetryPolicy { multiplier: f32::MAX, max_interval: Duration::MAX, ..policy() };
+        let mut session = MessageSession::new("N0CALL", growing, &clock, vec![]);
+        session.send("WU2Z", "Hello");
+        clock.advance(10);
+        assert_eq!(session.poll(), timed_out);
+        assert_eq!(session.transport().len(), 1);
+    }
+
+    #[test]
+    fn test_reply_acks_bounded() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        for i in 0..=super::MAX_REPLY_ACKS {
+            receive(&mut session, &format!("PEER{}>APRS::N0CALL   :Hi{{AB}}", i));
+        }
+        assert_eq!(session.reply_acks.len(), super::MAX_REPLY_ACKS);
+        // The oldest peer was dropped, the others are cleared once sent
+        session.send("PEER0", "Hello");
+        assert!(session.transport().last().unwrap().ends_with("{01}"));
+        session.send("PEER1", "Hello");
+        assert!(session.transport().last().unwrap().ends_with("{02}AB"));
+        session.send("PEER1", "Again");
+        assert!(session.transport().last().unwrap().ends_with("{03}"));
+        assert_eq!(session.reply_acks.len(), super::MAX_REPLY_ACKS - 1);
+    }
+
+    #[test]
+    fn test_third_party() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        let id = session.send("WU2Z", "Hello");
+        assert_eq!(receive(&mut session, "N1GATE>APRS:}WU2Z>APRS,TCPIP,N1GATE*::N0CALL   :ack01"),
+            [SessionEvent::Delivered { addressee: "WU2Z".to_string(), id }]);
+        receive(&mut session, "N1GATE>APRS:}WU2Z>APRS,TCPIP,N1GATE*::N0CALL   :Hi{7");
+        assert_eq!(session.transport()[1], "N0CALL>APRS::WU2Z     :ack7");
+    }
+
+    #[test]
+    fn test_skips_pending_ids() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        let first = session.send("WU2Z", "First");
+        for _ in 1..super::ID_SPACE - 1 {
+            let id = session.send("WU2Z", "Filler");
+            receive(&mut session, &format!("WU2Z>APRS::N0CALL   :ack{}", id));
+        }
+        // Wrapped around past `00` and `01` is still pending
+        assert_eq!(session.send("WU2Z", "Next"), "00");
+        assert_eq!(session.send("WU2Z", "Next"), "02");
+        assert_eq!(first, "01");
+        assert_eq!(session.pending(), 3);
+    }
+
+    #[test]
+    fn test_receive_ignores_others() {
+        let clock = TestClock(Cell::new(Instant::now()));
+        let mut session = MessageSession::new("N0CALL", policy(), &clock, vec![]);
+        assert!(receive(&mut session, "WU2Z>APRS::KB2ICI   :Hello{5}").is_empty());
+        assert!(receive(&mut session, "WU2Z>APRS:!4903.50N/07201.75W-").is_empty());
+        assert!(receive(&mut session, "WU2Z>APRS::N0CALL   :No ID").is_empty());
+        assert!(session.transport().is_empty());
+    }
+}
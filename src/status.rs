@@ -0,0 +1,207 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Degrees, ParseError, Position, Symbol, Timestamp, Watts};
+use crate::maidenhead;
+
+/// Beam heading and effective radiated power, the `^` status suffix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BeamHeading {
+    /// Multiple of 10 degrees.
+    pub heading: Degrees,
+    pub erp: Watts,
+}
+
+// Beam codes, `0`-`9` then `A`-`Z` for 10 to 35: tens of degrees of heading,
+// or the square root of a tenth of the ERP
+fn code_value(c: u8) -> Option<u32> {
+    match c {
+        b'0'..=b'9' => Some((c - b'0') as u32),
+        b'A'..=b'Z' => Some((c - b'A') as u32 + 10),
+        _ => None,
+    }
+}
+
+fn value_code(v: u32) -> char {
+    match v {
+        0..=9 => (b'0' + v as u8) as char,
+        _ => (b'A' + (v.min(35) - 10) as u8) as char,
+    }
+}
+
+impl BeamHeading {
+    fn parse(s: &str) -> Option<BeamHeading> {
+        match s.as_bytes() {
+            [b'^', h, p] => {
+                let p = code_value(*p)? as f32;
+                Some(BeamHeading { heading: Degrees(code_value(*h)? as f32 * 10.0), erp: Watts(p * p * 10.0) })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for BeamHeading {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // ERP is P^2 * 10 watts
+        let power = (self.erp.0 / 10.0).max(0.0).sqrt().round() as u32;
+        let heading = (self.heading.0.rem_euclid(360.0) / 10.0).round() as u32 % 36;
+        write!(f, "^{}{}", value_code(heading), value_code(power))
+    }
+}
+
+/// Status report, data type identifier `>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusReport {
+    /// Only sent with plain status text, always zulu.
+    pub timestamp: Option<Timestamp>,
+    /// Maidenhead locator, 4 or 6 characters.
+    pub locator: Option<String>,
+    /// Symbol table and code following the locator.
+    pub symbol: Option<(u8, u8)>,
+    /// Status text without the beam heading.
+    pub text: String,
+    pub beam: Option<BeamHeading>,
+}
+
+impl StatusReport {
+    /// Centre of the locator's grid square.
+    pub fn position(&self) -> Option<Position> {
+        maidenhead::decode(self.locator.as_ref()?)
+    }
+
+    pub fn symbol(&self) -> Option<Symbol> {
+        self.symbol.map(|(table, code)| Symbol::from_table(table, code))
+    }
+}
+
+// Locator of `len` characters followed by a symbol and the end or a space
+fn split_locator(s: &str, len: usize) -> Option<(&str, u8, u8, &str)> {
+    let b = s.as_bytes();
+    if b.len() < len + 2 || !b[len..len + 2].is_ascii() || !matches!(b.get(len + 2), None | Some(b' ')) {
+        return None;
+    }
+    let locator = s.get(..len)?;
+    maidenhead::decode(locator)?;
+    let rest = s.get(len + 2..)?;
+    Some((locator, b[len], b[len + 1], rest.strip_prefix(' ').unwrap_or("")))
+}
+
+impl FromStr for StatusReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let body = s.strip_prefix('>').ok_or_else(|| ParseError::InvalidStatus(s.to_string()))?;
+        let body = body.trim_end_matches(['\r', '\n']);
+
+        let (mut timestamp, mut locator, mut symbol, mut text) = (None, None, None, body);
+        let is_timestamp = body.len() >= 7 && body.as_bytes()[..6].iter().all(u8::is_ascii_digit)
+            && body.as_bytes()[6] == b'z';
+        if is_timestamp {
+            timestamp = Some(Timestamp::parse(&body[..7])?);
+            text = &body[7..];
+        } else if let Some((l, table, code, rest)) = split_locator(body, 6).or_else(|| split_locator(body, 4)) {
+            locator = Some(l.to_string());
+            symbol = Some((table, code));
+            text = rest;
+        }
+
+        let beam = text.len().checked_sub(3).and_then(|i| text.get(i..)).and_then(BeamHeading::parse);
+        if beam.is_some() {
+            text = &text[..text.len() - 3];
+        }
+        Ok(StatusReport { timestamp, locator, symbol, text: text.to_string(), beam })
+    }
+}
+
+impl Display for StatusReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, ">")?;
+        if let Some(v) = self.timestamp {
+            write!(f, "{}", v)?;
+        }
+        if let Some(v) = &self.locator {
+            let (table, code) = self.symbol.unwrap_or((b'/', b'/'));
+            write!(f, "{}{}{}", v, table as char, code as char)?;
+            if !self.text.is_empty() || self.beam.is_some() {
+                write!(f, " ")?;
+            }
+        }
+        write!(f, "{}", self.text)?;
+        if let Some(v) = self.beam {
+            write!(f, "{}", v)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Degrees, ParseError, StatusReport, Symbol, Timestamp, Watts};
+
+    #[test]
+    fn test_parse() {
+        let v: StatusReport = ">Net Control Center".parse().unwrap();
+        assert_eq!(v.text, "Net Control Center");
+        assert_eq!(v.timestamp, None);
+        assert_eq!(v.position(), None);
+
+        let v: StatusReport = ">092345zNet Control Center".parse().unwrap();
+        assert_eq!(v.timestamp, Some(Timestamp::DayHourMinute { day: 9, hour: 23, minute: 45, zulu: true }));
+        assert_eq!(v.text, "Net Control Center");
+
+        let v: StatusReport = ">IO91SX/G My house".parse().unwrap();
+        assert_eq!(v.locator.as_deref(), Some("IO91SX"));
+        assert_eq!(v.symbol(), Some(Symbol::GridSquare));
+        assert_eq!(v.text, "My house");
+        let p = v.position().unwrap();
+        assert_abs_diff_eq!(p.latitude, 51.97917, epsilon = 0.0001);
+        assert_abs_diff_eq!(p.longitude, -0.45833, epsilon = 0.0001);
+        assert_abs_diff_eq!(p.precision.unwrap().0, 15_190.3, epsilon = 0.1);
+
+        let v: StatusReport = ">IO91/G".parse().unwrap();
+        assert_eq!(v.locator.as_deref(), Some("IO91"));
+        assert_eq!(v.text, "");
+    }
+
+    #[test]
+    fn test_parse_beam_heading() {
+        let v: StatusReport = ">Beam south^B7".parse().unwrap();
+        assert_eq!(v.text, "Beam south");
+        let beam = v.beam.unwrap();
+        assert_eq!(beam.heading, Degrees(110.0));
+        assert_eq!(beam.erp, Watts(490.0));
+
+        let v: StatusReport = ">IO91SX/G ^90".parse().unwrap();
+        assert_eq!(v.text, "");
+        assert_eq!(v.beam.unwrap().heading, Degrees(90.0));
+        assert_eq!(v.beam.unwrap().erp, Watts(0.0));
+
+        let v: StatusReport = ">Price ^ 5".parse().unwrap();
+        assert_eq!(v.beam, None);
+        assert_eq!(v.text, "Price ^ 5");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!("Net".parse::<StatusReport>(), Err(ParseError::InvalidStatus("Net".to_string())));
+        assert!(">092375zText".parse::<StatusReport>().is_err());
+
+        // Text that isn't ASCII where the locator would be
+        for s in [">IO9é/", ">IO91é G", ">IO91Sé"] {
+            let v: StatusReport = s.parse().unwrap();
+            assert_eq!((v.locator, v.text.as_str()), (None, &s[1..]));
+        }
+        // Not a locator, kept as text
+        let v: StatusReport = ">ZZ99/G".parse().unwrap();
+        assert_eq!(v.locator, None);
+    }
+
+    #[test]
+    fn test_display() {
+        for s in [">Net Control Center", ">092345zNet Control Center", ">IO91SX/G My house", ">IO91/G",
+                  ">Beam south^B7", ">IO91SX/G ^90", ">"] {
+            assert_eq!(s.parse::<StatusReport>().unwrap().to_string(), s);
+        }
+    }
+}
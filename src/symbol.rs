@@ -1,6 +1,11 @@
 use crate::Symbol;
 
 pub fn from_table(table: u8, code: u8) -> Symbol {
+  // Overlay characters replace the alternate table identifier
+  let table = match table {
+    b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'j' => crate::ALT_SYMBOL_TABLE,
+    _ => table,
+  };
   match (table, code) {
     (0x2f, 0x21) => Symbol::Sheriff,                       // /!   BB     Police, Sheriff
     (0x5c, 0x21) => Symbol::Emergency,                     // \!   OBO    EMERGENCY (and overlays)
@@ -140,6 +145,146 @@ pub fn from_table(table: u8, code: u8) -> Symbol {
   }
 }
 
+pub fn to_table(symbol: &Symbol) -> Option<(u8, u8)> {
+  match symbol {
+    Symbol::Sheriff =>                 Some((0x2f, 0x21)),   // /!   BB     Police, Sheriff
+    Symbol::Emergency =>               Some((0x5c, 0x21)),   // \!   OBO    EMERGENCY (and overlays)
+    Symbol::Phone =>                   Some((0x2f, 0x24)),   // /$   BE     PHONE
+    Symbol::BankOrAtm =>               Some((0x5c, 0x24)),   // \$   OEO    Bank or ATM  (green box)
+    Symbol::PowerPlant =>              Some((0x5c, 0x25)),   // \%   OFO    Power Plant with overlay
+    Symbol::HfGateway =>               Some((0x2f, 0x26)),   // /&   BG     HF GATEway
+    Symbol::SmallAircraft =>           Some((0x2f, 0x27)),   // /'   BH     Small AIRCRAFT (SSID-11)
+    Symbol::IncidentSite =>            Some((0x5c, 0x27)),   // \'   OHO    Crash (& now Incident sites)
+    Symbol::MobileSatelliteStation =>  Some((0x2f, 0x28)),   // /(   BI     Mobile Satellite Station
+    Symbol::Cloudy =>                  Some((0x5c, 0x28)),   // \(   OIO    CLOUDY (other clouds w ovrly)
+    Symbol::Handicapped =>             Some((0x2f, 0x29)),   // /)   BJ     Wheelchair (handicapped)
+    Symbol::Firenet =>                 Some((0x5c, 0x29)),   // \)   OJO    Firenet MEO, MODIS Earth Obs.
+    Symbol::Snowmobile =>              Some((0x2f, 0x2a)),   // /*   BK     SnowMobile
+    Symbol::RedCross =>                Some((0x2f, 0x2b)),   // /+   BL     Red Cross
+    Symbol::Church =>                  Some((0x5c, 0x2b)),   // \+   OL     Church
+    Symbol::BoyScouts =>               Some((0x2f, 0x2c)),   // /,   BM     Boy Scouts
+    Symbol::GirlScouts =>              Some((0x5c, 0x2c)),   // \,   OM     Girl Scouts
+    Symbol::HouseQth =>                Some((0x2f, 0x2d)),   // /-   BN     House QTH (VHF)
+    Symbol::HouseHhf =>                Some((0x5c, 0x2d)),   // \-   ONO    House (H=HF) (O = Op Present)
+    Symbol::Cross =>                   Some((0x2f, 0x2e)),   // /.   BO     X
+    Symbol::BigQuestionMark =>         Some((0x5c, 0x2e)),   // \.   OO     Ambiguous (Big Question mark)
+    Symbol::RedDot =>                  Some((0x2f, 0x2f)),   // //   BP     Red Dot
+    Symbol::Waypoint =>                Some((0x5c, 0x2f)),   // \/   OP     Waypoint Destination
+    Symbol::Circle =>                  Some((0x5c, 0x30)),   // \0   A0#    CIRCLE (IRLP/Echolink/WIRES)
+    Symbol::WiFi =>                    Some((0x5c, 0x38)),   // \8   A8O    802.11 or other network node
+    Symbol::GasStation =>              Some((0x5c, 0x39)),   // \9   A9     Gas Station (blue pump)
+    Symbol::Fire =>                    Some((0x2f, 0x3a)),   // /:   MR     FIRE
+    Symbol::Campground =>              Some((0x2f, 0x3b)),   // /;   MS     Campground (Portable ops)
+    Symbol::Park =>                    Some((0x5c, 0x3b)),   // \;   NSO    Park/Picnic + overlay events
+    Symbol::Motorcycle =>              Some((0x2f, 0x3c)),   // /<   MT     Motorcycle     (SSID-10)
+    Symbol::Advisory =>                Some((0x5c, 0x3c)),   // \<   NTO    ADVISORY (one WX flag)
+    Symbol::RailroadEngine =>          Some((0x2f, 0x3d)),   // /=   MU     RAILROAD ENGINE
+    Symbol::Car =>                     Some((0x2f, 0x3e)),   // />   MV     CAR            (SSID-9)
+    Symbol::OverlayedVehicles =>       Some((0x5c, 0x3e)),   // \>   NV#    OVERLAYED CARs & Vehicles
+    Symbol::Server =>                  Some((0x2f, 0x3f)),   // /?   MW     SERVER for Files
+    Symbol::InfoKiosk =>               Some((0x5c, 0x3f)),   // \?   NW     INFO Kiosk  (Blue box with ?)
+    Symbol::Dot =>                     Some((0x2f, 0x40)),   // /@   MX     HC FUTURE predict (dot)
+    Symbol::Huricane =>                Some((0x5c, 0x40)),   // \@   NX     HURICANE/Trop-Storm
+    Symbol::AidStation =>              Some((0x2f, 0x41)),   // /A   PA     Aid Station
+    Symbol::BoxOverlay =>              Some((0x5c, 0x41)),   // \A   AA#    overlayBOX DTMF & RFID & XO
+    Symbol::Bbs =>                     Some((0x2f, 0x42)),   // /B   PB     BBS or PBBS
+    Symbol::Canoe =>                   Some((0x2f, 0x43)),   // /C   PC     Canoe
+    Symbol::CoastGuard =>              Some((0x5c, 0x43)),   // \C   AC     Coast Guard
+    Symbol::Depots =>                  Some((0x5c, 0x44)),   // \D   ADO    DEPOTS (Drizzle ==> ' ovly D)
+    Symbol::Eyeball =>                 Some((0x2f, 0x45)),   // /E   PE     EYEBALL (Events, etc!)
+    Symbol::Smoke =>                   Some((0x5c, 0x45)),   // \E   AE     Smoke (& other vis codes)
+    Symbol::Tractor =>                 Some((0x2f, 0x46)),   // /F   PF     Farm Vehicle (tractor)
+    Symbol::GridSquare =>              Some((0x2f, 0x47)),   // /G   PG     Grid Square (6 digit)
+    Symbol::SnowShower =>              Some((0x5c, 0x47)),   // \G   AG     AVAIL (Snow Shwr ==> I ovly S)
+    Symbol::Hotel =>                   Some((0x2f, 0x48)),   // /H   PH     HOTEL (blue bed symbol)
+    Symbol::Haze =>                    Some((0x5c, 0x48)),   // \H   AHO    \Haze (& Overlay Hazards)
+    Symbol::NetworkStation =>          Some((0x2f, 0x49)),   // /I   PI     TcpIp on air network stn
+    Symbol::RainShower =>              Some((0x5c, 0x49)),   // \I   AI     Rain Shower
+    Symbol::Lightening =>              Some((0x5c, 0x4a)),   // \J   AJ     AVAIL (Lightening ==> I ovly L)
+    Symbol::School =>                  Some((0x2f, 0x4b)),   // /K   PK     School
+    Symbol::KenwoodHt =>               Some((0x5c, 0x4b)),   // \K   AK     Kenwood HT (W)
+    Symbol::PcUser =>                  Some((0x2f, 0x4c)),   // /L   PL     PC user (Jan 03)
+    Symbol::Lighthouse =>              Some((0x5c, 0x4c)),   // \L   AL     Lighthouse
+    Symbol::MacAprs =>                 Some((0x2f, 0x4d)),   // /M   PM     MacAPRS
+    Symbol::Military =>                Some((0x5c, 0x4d)),   // \M   AMO    MARS (A=Army,N=Navy,F=AF)
+    Symbol::NtsStation =>              Some((0x2f, 0x4e)),   // /N   PN     NTS Station
+    Symbol::NavigationBuoy =>          Some((0x5c, 0x4e)),   // \N   AN     Navigation Buoy
+    Symbol::Balloon =>                 Some((0x2f, 0x4f)),   // /O   PO     BALLOON        (SSID-11)
+    Symbol::Rocket =>                  Some((0x5c, 0x4f)),   // \O   AO     Overlay Balloon (Rocket = \O)
+    Symbol::Police =>                  Some((0x2f, 0x50)),   // /P   PP     Police
+    Symbol::Parking =>                 Some((0x5c, 0x50)),   // \P   AP     Parking
+    Symbol::Quake =>                   Some((0x5c, 0x51)),   // \Q   AQ     QUAKE
+    Symbol::RecVehicle =>              Some((0x2f, 0x52)),   // /R   PR     REC. VEHICLE   (SSID-13)
+    Symbol::Restaurant =>              Some((0x5c, 0x52)),   // \R   ARO    Restaurant
+    Symbol::Shuttle =>                 Some((0x2f, 0x53)),   // /S   PS     SHUTTLE
+    Symbol::Satellite =>               Some((0x5c, 0x53)),   // \S   AS     Satellite/Pacsat
+    Symbol::Sstv =>                    Some((0x2f, 0x54)),   // /T   PT     SSTV
+    Symbol::Thunderstorm =>            Some((0x5c, 0x54)),   // \T   AT     Thunderstorm
+    Symbol::Bus =>                     Some((0x2f, 0x55)),   // /U   PU     BUS            (SSID-2)
+    Symbol::Sunny =>                   Some((0x5c, 0x55)),   // \U   AU     SUNNY
+    Symbol::Atv =>                     Some((0x2f, 0x56)),   // /V   PV     ATV
+    Symbol::VortacNavAid =>            Some((0x5c, 0x56)),   // \V   AV     VORTAC Nav Aid
+    Symbol::NationalWxServiceSite =>   Some((0x2f, 0x57)),   // /W   PW     National WX Service Site
+    Symbol::NwsSite =>                 Some((0x5c, 0x57)),   // \W   AW#    # NWS site (NWS options)
+    Symbol::Helo =>                    Some((0x2f, 0x58)),   // /X   PX     HELO           (SSID-6)
+    Symbol::Pharmacy =>                Some((0x5c, 0x58)),   // \X   AX     Pharmacy Rx (Apothicary)
+    Symbol::Yacht =>                   Some((0x2f, 0x59)),   // /Y   PY     YACHT (sail)   (SSID-5)
+    Symbol::RadiosAndDevices =>        Some((0x5c, 0x59)),   // \Y   AYO    Radios and devices
+    Symbol::WinAprs =>                 Some((0x2f, 0x5a)),   // /Z   PZ     WinAPRS
+    Symbol::Person =>                  Some((0x2f, 0x5b)),   // /[   HS     Human/Person   (SSID-7)
+    Symbol::CloudOrHumansWOvrly =>     Some((0x5c, 0x5b)),   // \[   DSO    W.Cloud (& humans w Ovrly)
+    Symbol::Triangle =>                Some((0x2f, 0x5c)),   // /\   HT     TRIANGLE(DF station)
+    Symbol::GpsSymbol =>               Some((0x5c, 0x5c)),   // \\   DTO    New overlayable GPS symbol
+    Symbol::PostOffice =>              Some((0x2f, 0x5d)),   // /]   HU     MAIL/PostOffice(was PBBS)
+    Symbol::LargeAircraft =>           Some((0x2f, 0x5e)),   // /^   HV     LARGE AIRCRAFT
+    Symbol::OtherAircraft =>           Some((0x5c, 0x5e)),   // \^   DV#    other Aircraft ovrlys (2014)
+    Symbol::WeatherStation =>          Some((0x2f, 0x5f)),   // /_   HW     WEATHER Station (blue)
+    Symbol::WxSite =>                  Some((0x5c, 0x5f)),   // \_   DW#    # WX site (green digi)
+    Symbol::DishAntenna =>             Some((0x2f, 0x60)),   // /`   HX     Dish Antenna
+    Symbol::Rain =>                    Some((0x5c, 0x60)),   // \`   DX     Rain (all types w ovrly)
+    Symbol::Ambulance =>               Some((0x2f, 0x61)),   // /a   LA     AMBULANCE     (SSID-1)
+    Symbol::Bike =>                    Some((0x2f, 0x62)),   // /b   LB     BIKE          (SSID-4)
+    Symbol::IncidentCommandPost =>     Some((0x2f, 0x63)),   // /c   LC     Incident Command Post
+    Symbol::FireDept =>                Some((0x2f, 0x64)),   // /d   LD     Fire dept
+    Symbol::Horse =>                   Some((0x2f, 0x65)),   // /e   LE     HORSE (equestrian)
+    Symbol::Sleet =>                   Some((0x5c, 0x65)),   // \e   SE     Sleet (& future ovrly codes)
+    Symbol::FireTruck =>               Some((0x2f, 0x66)),   // /f   LF     FIRE TRUCK    (SSID-3)
+    Symbol::FunnelCloud =>             Some((0x5c, 0x66)),   // \f   SF     Funnel Cloud
+    Symbol::Glider =>                  Some((0x2f, 0x67)),   // /g   LG     Glider
+    Symbol::Gale =>                    Some((0x5c, 0x67)),   // \g   SG     Gale Flags
+    Symbol::Hospital =>                Some((0x2f, 0x68)),   // /h   LH     HOSPITAL
+    Symbol::Store =>                   Some((0x5c, 0x68)),   // \h   SHO    Store. or HAMFST Hh=HAM store
+    Symbol::PointOfInterest =>         Some((0x5c, 0x69)),   // \i   SI#    BOX or points of Interest
+    Symbol::Jeep =>                    Some((0x2f, 0x6a)),   // /j   LJ     JEEP          (SSID-12)
+    Symbol::Workzone =>                Some((0x5c, 0x6a)),   // \j   SJ     WorkZone (Steam Shovel)
+    Symbol::Truck =>                   Some((0x2f, 0x6b)),   // /k   LK     TRUCK         (SSID-14)
+    Symbol::SpecialVehicle =>          Some((0x5c, 0x6b)),   // \k   SKO    Special Vehicle SUV,ATV,4x4
+    Symbol::Laptop =>                  Some((0x2f, 0x6c)),   // /l   LL     Laptop (Jan 03)  (Feb 07)
+    Symbol::Areas =>                   Some((0x5c, 0x6c)),   // \l   SL     Areas      (box,circles,etc)
+    Symbol::MicERepeater =>            Some((0x2f, 0x6d)),   // /m   LM     Mic-E Repeater
+    Symbol::ValueSign =>               Some((0x5c, 0x6d)),   // \m   SM     Value Sign (3 digit display)
+    Symbol::Node =>                    Some((0x2f, 0x6e)),   // /n   LN     Node (black bulls-eye)
+    Symbol::OverlayTriangle =>         Some((0x5c, 0x6e)),   // \n   SN#    OVERLAY TRIANGLE
+    Symbol::SmallCircle =>             Some((0x5c, 0x6f)),   // \o   SO     small circle
+    Symbol::Dog =>                     Some((0x2f, 0x70)),   // /p   LP     ROVER (puppy, or dog)
+    Symbol::Restroom =>                Some((0x5c, 0x72)),   // \r   SR     Restrooms
+    Symbol::Boat =>                    Some((0x2f, 0x73)),   // /s   LS     SHIP (pwr boat)  (SSID-8)
+    Symbol::OverlayBoat =>             Some((0x5c, 0x73)),   // \s   SS#    OVERLAY SHIP/boats
+    Symbol::TruckStop =>               Some((0x2f, 0x74)),   // /t   LT     TRUCK STOP
+    Symbol::Tornado =>                 Some((0x5c, 0x74)),   // \t   ST     Tornado
+    Symbol::Truck18Wheeler =>          Some((0x2f, 0x75)),   // /u   LU     TRUCK (18 wheeler)
+    Symbol::OverlayedTruck =>          Some((0x5c, 0x75)),   // \u   SU#    OVERLAYED TRUCK
+    Symbol::Van =>                     Some((0x2f, 0x76)),   // /v   LV     VAN           (SSID-15)
+    Symbol::OverlayedVan =>            Some((0x5c, 0x76)),   // \v   SV#    OVERLAYED Van
+    Symbol::WaterStation =>            Some((0x2f, 0x77)),   // /w   LW     WATER station
+    Symbol::Flooding =>                Some((0x5c, 0x77)),   // \w   SWO    Flooding (Avalanches/Slides)
+    Symbol::Wreck =>                   Some((0x5c, 0x78)),   // \x   SX     Wreck or Obstruction ->X<-
+    Symbol::Skywarn =>                 Some((0x5c, 0x79)),   // \y   SY     Skywarn
+    Symbol::OverlayedShelter =>        Some((0x5c, 0x7a)),   // \z   SZ#    OVERLAYED Shelter
+    Symbol::Other => None,
+  }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Symbol;
@@ -152,6 +297,20 @@ mod tests {
          assert_eq!( Symbol::from_table(ALT_SYMBOL_TABLE, 0x3f), Symbol::InfoKiosk );
     }
 
+    #[test]
+    fn test_overlay_symbol() {
+         assert_eq!( Symbol::from_table(b'S', 0x23), Symbol::Other );
+         assert_eq!( Symbol::from_table(b'E', 0x3e), Symbol::OverlayedVehicles );
+         assert_eq!( Symbol::from_table(b'3', 0x3e), Symbol::OverlayedVehicles );
+    }
+
+    #[test]
+    fn test_symbol_to_table() {
+         assert_eq!( Symbol::Server.to_table(), Some((PRIMARY_SYMBOL_TABLE, 0x3f)) );
+         assert_eq!( Symbol::InfoKiosk.to_table(), Some((ALT_SYMBOL_TABLE, 0x3f)) );
+         assert_eq!( Symbol::Other.to_table(), None );
+    }
+
     #[test]
     fn test_unknown_symbol() {
          assert_eq!( Symbol::from_table(ALT_SYMBOL_TABLE, 0xff), Symbol::Other );
@@ -0,0 +1,269 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::ParseError;
+use crate::base91;
+
+// Largest value of a 2-digit base-91 number
+const BASE91_MAX: u32 = 91 * 91 - 1;
+
+/// Sequence number of a telemetry report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TelemetrySequence {
+    /// Usually 3 digits, although larger counters are common.
+    Number(u16),
+    /// `MIC`, sent by Mic-E trackers.
+    Mic,
+}
+
+/// Telemetry report, data type identifier `T#`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Telemetry {
+    pub sequence: TelemetrySequence,
+    /// Raw analog values A1 to A5, `None` if not sent.
+    pub analog: [Option<f32>; 5],
+    /// Digital values, bit 0 is B1.
+    pub digital: Option<u8>,
+    /// Text after the digital values, or after the analog ones when there
+    /// are no digital values and it doesn't start with a bit.
+    pub comment: String,
+}
+
+impl Telemetry {
+    /// Value of digital channel `n`, 1 to 8.
+    pub fn bit(&self, n: u8) -> Option<bool> {
+        if !(1..=8).contains(&n) {
+            return None;
+        }
+        self.digital.map(|v| v & (1 << (n - 1)) != 0)
+    }
+
+    /// Base-91 comment telemetry `|ss1122334455dd|`, `None` for values it can't
+    /// carry: `MIC` sequence, gaps in the analog channels, values outside
+    /// 0 to 8280, or bits without all five analog channels.
+    pub fn encode_comment(&self) -> Option<String> {
+        let sequence = match self.sequence {
+            TelemetrySequence::Number(v) if v as u32 <= BASE91_MAX => v as u32,
+            _ => return None,
+        };
+        let count = self.analog.iter().take_while(|v| v.is_some()).count();
+        if count == 0 || self.analog[count..].iter().any(Option::is_some) || (self.digital.is_some() && count < 5) {
+            return None;
+        }
+        let mut s = format!("|{}", base91::encode(sequence, 2));
+        for v in self.analog.iter().flatten() {
+            let v = v.round();
+            if !(0.0..=BASE91_MAX as f32).contains(&v) {
+                return None;
+            }
+            s.push_str(&base91::encode(v as u32, 2));
+        }
+        if let Some(v) = self.digital {
+            s.push_str(&base91::encode(v as u32, 2));
+        }
+        s.push('|');
+        Some(s)
+    }
+}
+
+// Base-91 `|ss11...|` telemetry embedded in a comment, and the comment
+// without it
+pub(crate) fn split_comment(comment: &str) -> Option<(Telemetry, String)> {
+    let start = comment.find('|')?;
+    let len = comment[start + 1..].find('|')?;
+    let data = &comment.as_bytes()[start + 1..start + 1 + len];
+    if !(4..=14).contains(&len) || len % 2 != 0 {
+        return None;
+    }
+    let values = data.chunks(2).map(base91::decode).collect::<Option<Vec<_>>>()?;
+    let mut analog = [None; 5];
+    for (a, v) in analog.iter_mut().zip(&values[1..]) {
+        *a = Some(*v as f32);
+    }
+    let digital = match values.get(6) {
+        Some(&v) => Some(u8::try_from(v).ok()?),
+        None => None,
+    };
+    let telemetry = Telemetry {
+        sequence: TelemetrySequence::Number(values[0] as u16),
+        analog,
+        digital,
+        comment: String::new(),
+    };
+    Some((telemetry, format!("{}{}", &comment[..start], &comment[start + len + 2..])))
+}
+
+impl FromStr for Telemetry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidTelemetry(s.to_string());
+        let body = s.strip_prefix("T#").ok_or_else(err)?.trim_end_matches(['\r', '\n']);
+        // Fields are split off one at a time, so commas in the comment stay
+        fn split(v: &str) -> (&str, Option<&str>) {
+            match v.split_once(',') {
+                Some((field, rest)) => (field, Some(rest)),
+                None => (v, None),
+            }
+        }
+
+        // `MIC` may or may not be followed by a comma
+        let (sequence, mut rest) = match body.strip_prefix("MIC") {
+            Some("") => (TelemetrySequence::Mic, None),
+            Some(v) => (TelemetrySequence::Mic, Some(v.strip_prefix(',').unwrap_or(v))),
+            None => {
+                let (first, rest) = split(body);
+                (TelemetrySequence::Number(first.trim().parse().map_err(|_| err())?), rest)
+            }
+        };
+
+        let mut analog = [None; 5];
+        for v in analog.iter_mut() {
+            let Some((field, tail)) = rest.map(split) else { break };
+            rest = tail;
+            *v = match field.trim() {
+                "" => None,
+                x => Some(x.parse().map_err(|_| err())?),
+            };
+        }
+        if analog.iter().all(Option::is_none) {
+            return Err(err());
+        }
+
+        let (digital, comment) = match rest {
+            Some(v) if !v.starts_with(['0', '1']) => (None, v.to_string()),
+            Some(v) => {
+                let bits = v.get(..8).filter(|b| b.bytes().all(|c| c == b'0' || c == b'1')).ok_or_else(err)?;
+                let digital = bits.bytes().rev().fold(0u8, |acc, c| (acc << 1) | (c - b'0'));
+                (Some(digital), v[8..].to_string())
+            }
+            None => (None, String::new()),
+        };
+        Ok(Telemetry { sequence, analog, digital, comment })
+    }
+}
+
+impl Display for Telemetry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // `MIC` is written the APRS101 way, without a comma
+        let mut separator = match self.sequence {
+            TelemetrySequence::Number(v) => {
+                write!(f, "T#{:03}", v)?;
+                ","
+            }
+            TelemetrySequence::Mic => {
+                write!(f, "T#MIC")?;
+                ""
+            }
+        };
+        for v in &self.analog {
+            write!(f, "{}", separator)?;
+            if let Some(v) = v {
+                write!(f, "{:03}", v)?;
+            }
+            separator = ",";
+        }
+        if let Some(v) = self.digital {
+            write!(f, ",")?;
+            for i in 0..8 {
+                write!(f, "{}", (v >> i) & 1)?;
+            }
+        } else if !self.comment.is_empty() {
+            write!(f, ",")?;
+        }
+        write!(f, "{}", self.comment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_comment;
+    use crate::{ParseError, Telemetry, TelemetrySequence};
+
+    #[test]
+    fn test_parse() {
+        let v: Telemetry = "T#005,199,000,255,073,123,01101001".parse().unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Number(5));
+        assert_eq!(v.analog, [Some(199.0), Some(0.0), Some(255.0), Some(73.0), Some(123.0)]);
+        assert_eq!(v.digital, Some(0b1001_0110));
+        assert_eq!(v.bit(1), Some(false));
+        assert_eq!(v.bit(2), Some(true));
+        assert_eq!(v.bit(8), Some(true));
+        assert_eq!(v.bit(9), None);
+        assert_eq!(v.comment, "");
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        let v: Telemetry = "T#MIC199,000,255,073,123,01101001".parse().unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Mic);
+        assert_eq!(v.analog[0], Some(199.0));
+
+        let v: Telemetry = "T#MIC,199,000,255,073,123,01101001".parse().unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Mic);
+        assert_eq!(v.analog[4], Some(123.0));
+
+        let v: Telemetry = "T#1234,1023,-5,12.5,,7,00000001Solar site".parse().unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Number(1234));
+        assert_eq!(v.analog, [Some(1023.0), Some(-5.0), Some(12.5), None, Some(7.0)]);
+        assert_eq!(v.digital, Some(0b1000_0000));
+        assert_eq!(v.comment, "Solar site");
+
+        // Commas in the comment are kept, also after `MIC` without a comma
+        let v: Telemetry = "T#MIC199,000,255,073,123,01101001Bal,loon".parse().unwrap();
+        assert_eq!(v.analog[4], Some(123.0));
+        assert_eq!(v.comment, "Bal,loon");
+        let v: Telemetry = "T#001,1,2,3,4,5,Bal,loon".parse().unwrap();
+        assert_eq!((v.digital, v.comment.as_str()), (None, "Bal,loon"));
+
+        let v: Telemetry = "T#001,100,200".parse().unwrap();
+        assert_eq!(v.analog, [Some(100.0), Some(200.0), None, None, None]);
+        assert_eq!(v.digital, None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["T#", "T#abc,1,2,3,4,5", "T#001,x,2", "T#001,1,2,3,4,5,0110", "#001,1,2,3,4,5", "T#001"] {
+            assert_eq!(s.parse::<Telemetry>(), Err(ParseError::InvalidTelemetry(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["T#005,199,000,255,073,123,01101001", "T#MIC199,000,255,073,123,01101001",
+                  "T#1234,1023,-05,12.5,,007,00000001Solar site", "T#001,100,200,,,",
+                  "T#MIC199,000,255,073,123,01101001Bal,loon", "T#001,001,002,003,004,005,Solar site"] {
+            assert_eq!(s.parse::<Telemetry>().unwrap().to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_comment_telemetry() {
+        let (v, rest) = split_comment("Balloon |ss11223344|up").unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Number(7544));
+        assert_eq!(v.analog, [Some(1472.0), Some(1564.0), Some(1656.0), Some(1748.0), None]);
+        assert_eq!(v.digital, None);
+        assert_eq!(rest, "Balloon up");
+
+        let (v, rest) = split_comment("|!\"!!!!!!!!!!!$|").unwrap();
+        assert_eq!(v.sequence, TelemetrySequence::Number(1));
+        assert_eq!(v.analog, [Some(0.0); 5]);
+        assert_eq!(v.digital, Some(3));
+        assert_eq!(rest, "");
+
+        for s in ["|ss1|", "|ss11", "|ss11 |", "no pipes", "|ss112233445566{{|"] {
+            assert_eq!(split_comment(s), None);
+        }
+    }
+
+    #[test]
+    fn test_encode_comment() {
+        for s in ["|ss11223344|", "|!\"!!!!!!!!!!!$|", "|{{{{|"] {
+            let (v, _) = split_comment(s).unwrap();
+            assert_eq!(v.encode_comment().as_deref(), Some(s));
+        }
+        for s in ["T#MIC,1,2,3,4,5", "T#001,1,,3", "T#001,1,2,3,4,,00000001", "T#001,9000", "T#9000,1"] {
+            assert_eq!(s.parse::<Telemetry>().unwrap().encode_comment(), None);
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{AprsData, AprsPacket, Message, MessageKind, ParseError, Telemetry, TelemetrySequence};
+
+/// Scaling of a raw analog value to `a*x^2 + b*x + c`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Equation {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl Equation {
+    pub fn apply(&self, x: f32) -> f32 {
+        self.a * x * x + self.b * x + self.c
+    }
+}
+
+impl Default for Equation {
+    /// Identity, used until a station sends `EQNS`.
+    fn default() -> Self {
+        Equation { a: 0.0, b: 1.0, c: 0.0 }
+    }
+}
+
+/// Telemetry metadata message a station sends to itself, or has sent on its
+/// behalf, to describe its `T#` reports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TelemetryMetadata {
+    /// `PARM.`: names of A1 to A5, then B1 to B8.
+    Parameters(Vec<String>),
+    /// `UNIT.`: units of A1 to A5, then labels of B1 to B8.
+    Units(Vec<String>),
+    /// `EQNS.`: `a,b,c` for each of A1 to A5.
+    Equations([Equation; 5]),
+    /// `BITS.`: bit values in which B1 to B8 are active and the project title.
+    Bits { sense: u8, title: String },
+}
+
+impl TelemetryMetadata {
+    /// Message carrying the metadata of `station`.
+    pub fn to_message(&self, station: &str) -> Message {
+        Message {
+            addressee: station.to_string(),
+            kind: MessageKind::Text { text: self.to_string(), id: None, reply_ack: None },
+        }
+    }
+}
+
+impl FromStr for TelemetryMetadata {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseError::InvalidTelemetryMetadata(s.to_string());
+        let (kind, body) = s.split_once('.').ok_or_else(err)?;
+        let body = body.trim_end();
+        let list = || body.split(',').take(13).map(|v| v.trim().to_string()).collect();
+        match kind {
+            "PARM" => Ok(TelemetryMetadata::Parameters(list())),
+            "UNIT" => Ok(TelemetryMetadata::Units(list())),
+            "EQNS" => {
+                let values = body.split(',').take(15)
+                    .map(|v| match v.trim() {
+                        "" => Ok(None),
+                        v => v.parse::<f32>().map(Some).map_err(|_| err()),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut equations = [Equation::default(); 5];
+                for (v, c) in equations.iter_mut().zip(values.chunks(3)) {
+                    let d = Equation::default();
+                    v.a = c.first().copied().flatten().unwrap_or(d.a);
+                    v.b = c.get(1).copied().flatten().unwrap_or(d.b);
+                    v.c = c.get(2).copied().flatten().unwrap_or(d.c);
+                }
+                Ok(TelemetryMetadata::Equations(equations))
+            }
+            "BITS" => {
+                let (bits, title) = body.split_once(',').unwrap_or((body, ""));
+                if bits.len() != 8 || !bits.bytes().all(|c| c == b'0' || c == b'1') {
+                    return Err(err());
+                }
+                let sense = bits.bytes().rev().fold(0u8, |acc, c| (acc << 1) | (c - b'0'));
+                Ok(TelemetryMetadata::Bits { sense, title: title.to_string() })
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Display for TelemetryMetadata {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            TelemetryMetadata::Parameters(v) => write!(f, "PARM.{}", v.join(",")),
+            TelemetryMetadata::Units(v) => write!(f, "UNIT.{}", v.join(",")),
+            TelemetryMetadata::Equations(v) => {
+                write!(f, "EQNS.")?;
+                let values: Vec<_> = v.iter().flat_map(|v| [v.a, v.b, v.c]).map(|v| v.to_string()).collect();
+                write!(f, "{}", values.join(","))
+            }
+            TelemetryMetadata::Bits { sense, title } => {
+                write!(f, "BITS.")?;
+                for i in 0..8 {
+                    write!(f, "{}", (sense >> i) & 1)?;
+                }
+                write!(f, ",{}", title)
+            }
+        }
+    }
+}
+
+/// Everything a station has told about its telemetry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryDefinitions {
+    /// Names of A1 to A5, then B1 to B8.
+    pub names: Vec<String>,
+    /// Units of A1 to A5, then labels of B1 to B8.
+    pub units: Vec<String>,
+    pub equations: [Equation; 5],
+    /// Bit values in which B1 to B8 are active, bit 0 is B1.
+    pub sense: u8,
+    pub title: String,
+}
+
+impl Default for TelemetryDefinitions {
+    fn default() -> Self {
+        TelemetryDefinitions {
+            names: vec![],
+            units: vec![],
+            equations: [Equation::default(); 5],
+            // APRS101 default, all bits active when set
+            sense: 0xff,
+            title: String::new(),
+        }
+    }
+}
+
+impl TelemetryDefinitions {
+    pub fn update(&mut self, metadata: TelemetryMetadata) {
+        match metadata {
+            TelemetryMetadata::Parameters(v) => self.names = v,
+            TelemetryMetadata::Units(v) => self.units = v,
+            TelemetryMetadata::Equations(v) => self.equations = v,
+            TelemetryMetadata::Bits { sense, title } => {
+                self.sense = sense;
+                self.title = title;
+            }
+        }
+    }
+
+    fn label(list: &[String], i: usize) -> String {
+        list.get(i).cloned().unwrap_or_default()
+    }
+
+    /// Named and scaled values of a telemetry report.
+    pub fn decode(&self, telemetry: &Telemetry) -> DecodedTelemetry {
+        let analog = telemetry.analog.iter().zip(&self.equations).enumerate()
+            .filter_map(|(i, (v, eq))| Some(AnalogValue {
+                channel: i as u8 + 1,
+                name: TelemetryDefinitions::label(&self.names, i),
+                unit: TelemetryDefinitions::label(&self.units, i),
+                value: eq.apply((*v)?),
+            }))
+            .collect();
+        let digital = telemetry.digital.map(|bits| (0..8)
+            .map(|i| DigitalValue {
+                channel: i + 1,
+                name: TelemetryDefinitions::label(&self.names, 5 + i as usize),
+                label: TelemetryDefinitions::label(&self.units, 5 + i as usize),
+                value: bits & (1 << i) != 0,
+                active: (bits ^ !self.sense) & (1 << i) != 0,
+            })
+            .collect())
+            .unwrap_or_default();
+        DecodedTelemetry { sequence: telemetry.sequence, analog, digital }
+    }
+}
+
+/// Analog channel in engineering units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogValue {
+    /// 1 to 5.
+    pub channel: u8,
+    pub name: String,
+    pub unit: String,
+    pub value: f32,
+}
+
+/// Digital channel with its meaning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitalValue {
+    /// 1 to 8.
+    pub channel: u8,
+    pub name: String,
+    /// Label to show while the bit is active.
+    pub label: String,
+    pub value: bool,
+    /// Bit matches the station's `BITS` sense.
+    pub active: bool,
+}
+
+/// Telemetry report with definitions applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedTelemetry {
+    pub sequence: TelemetrySequence,
+    /// Channels present in the report.
+    pub analog: Vec<AnalogValue>,
+    /// All 8 bits, empty if the report had none.
+    pub digital: Vec<DigitalValue>,
+}
+
+/// Telemetry definitions of the stations heard, keyed by callsign.
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryRegistry {
+    stations: HashMap<String, TelemetryDefinitions>,
+}
+
+impl TelemetryRegistry {
+    pub fn new() -> Self {
+        TelemetryRegistry::default()
+    }
+
+    pub fn update(&mut self, station: &str, metadata: TelemetryMetadata) {
+        self.stations.entry(station.to_string()).or_default().update(metadata);
+    }
+
+    pub fn definitions(&self, station: &str) -> Option<&TelemetryDefinitions> {
+        self.stations.get(station)
+    }
+
+    /// Stores metadata messages and decodes telemetry reports, also when gated
+    /// as third-party traffic, returns `None` for any other packet.
+    pub fn receive(&mut self, packet: &AprsPacket) -> Option<DecodedTelemetry> {
+        let packet = packet.innermost();
+        match &packet.data {
+            AprsData::Message(v) => {
+                if let Some(metadata) = v.text().and_then(|v| v.parse().ok()) {
+                    self.update(&v.addressee, metadata);
+                }
+                None
+            }
+            AprsData::Telemetry(v) => Some(self.decode(&packet.source, v)),
+            _ => None,
+        }
+    }
+
+    /// Decodes `telemetry` of `station`, with identity scaling if nothing is known about it.
+    pub fn decode(&self, station: &str, telemetry: &Telemetry) -> DecodedTelemetry {
+        match self.stations.get(station) {
+            Some(v) => v.decode(telemetry),
+            None => TelemetryDefinitions::default().decode(telemetry),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AprsPacket, Equation, ParseError, TelemetryMetadata, TelemetryRegistry};
+
+    #[test]
+    fn test_parse_metadata() {
+        let v: TelemetryMetadata = "PARM.Battery,Btemp,ATemp,Pres,Alt,Camra,Chute,Sun,10m,ATV".parse().unwrap();
+        match v {
+            TelemetryMetadata::Parameters(v) => assert_eq!(v[..3], ["Battery", "Btemp", "ATemp"]),
+            v => panic!("unexpected {:?}", v),
+        }
+
+        let v: TelemetryMetadata = "EQNS.0,5.2,0,0,.53,-32,3,4.39,49,-32,3".parse().unwrap();
+        let TelemetryMetadata::Equations(eq) = v else { panic!() };
+        assert_eq!(eq[0], Equation { a: 0.0, b: 5.2, c: 0.0 });
+        assert_eq!(eq[2], Equation { a: 3.0, b: 4.39, c: 49.0 });
+        assert_eq!(eq[3], Equation { a: -32.0, b: 3.0, c: 0.0 });
+        assert_eq!(eq[4], Equation::default());
+
+        let v: TelemetryMetadata = "BITS.10110000,N0QBF's Big Balloon".parse().unwrap();
+        assert_eq!(v, TelemetryMetadata::Bits { sense: 0b0000_1101, title: "N0QBF's Big Balloon".to_string() });
+
+        for s in ["PARM", "BITS.1011", "EQNS.1,x,3", "TEXT.Hello"] {
+            assert_eq!(s.parse::<TelemetryMetadata>(), Err(ParseError::InvalidTelemetryMetadata(s.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["PARM.Battery,Btemp,,Pres", "UNIT.v/100,deg.F,deg.F,Mbar,Kft,Click,OPEN,on,on,hi",
+                  "EQNS.0,5.2,0,0,0.53,-32,3,4.39,49,-32,3,18,1,2,3", "BITS.10110000,Project"] {
+            assert_eq!(s.parse::<TelemetryMetadata>().unwrap().to_string(), s);
+        }
+        let v: TelemetryMetadata = "BITS.11111111,".parse().unwrap();
+        assert_eq!(v.to_message("N0QBF-11").to_string(), ":N0QBF-11 :BITS.11111111,");
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = TelemetryRegistry::new();
+        for line in ["N0QBF-11>APRS::N0QBF-11 :PARM.Battery,Btemp,ATemp,Pres,Alt,Camra,Chute,Sun,10m,ATV",
+                     "N0QBF-11>APRS::N0QBF-11 :UNIT.v/100,deg.F,deg.F,Mbar,Kft,Click,OPEN,on,on,hi",
+                     "N0QBF-11>APRS::N0QBF-11 :EQNS.0,5.2,0,0,.53,-32,3,4.39,49,-32,3,18,1,2,3",
+                     "N0QBF-11>APRS::N0QBF-11 :BITS.10110000,N0QBF's Big Balloon"] {
+            assert_eq!(registry.receive(&line.parse().unwrap()), None);
+        }
+        assert_eq!(registry.definitions("N0QBF-11").unwrap().title, "N0QBF's Big Balloon");
+
+        let p: AprsPacket = "N0QBF-11>APRS:T#005,199,000,255,073,123,01101001".parse().unwrap();
+        let v = registry.receive(&p).unwrap();
+        assert_eq!(v.analog.len(), 5);
+        assert_eq!((v.analog[0].name.as_str(), v.analog[0].unit.as_str()), ("Battery", "v/100"));
+        assert_abs_diff_eq!(v.analog[0].value, 1034.8, epsilon = 0.01);
+        assert_abs_diff_eq!(v.analog[1].value, -32.0, epsilon = 0.01);
+        assert_abs_diff_eq!(v.analog[2].value, 196_243.45, epsilon = 0.1);
+
+        // Active when the bit matches the sense, 01101001 against 10110000
+        assert_eq!(v.digital.len(), 8);
+        assert_eq!((v.digital[0].name.as_str(), v.digital[0].label.as_str()), ("Camra", "Click"));
+        assert!(!v.digital[0].value && !v.digital[0].active);
+        assert!(v.digital[1].value && !v.digital[1].active);
+        assert!(v.digital[2].value && v.digital[2].active);
+        assert!(v.digital[4].value && !v.digital[4].active);
+        assert!(!v.digital[5].value && v.digital[5].active);
+
+        // Third-party traffic is decoded as the originating station
+        let p: AprsPacket = "IGATE>APRS:}N0QBF-11>APRS,TCPIP,IGATE*:T#006,199,000,255,073,123,01101001".parse().unwrap();
+        assert_eq!(registry.receive(&p).unwrap().analog[0].name, "Battery");
+        let p: AprsPacket = "IGATE>APRS:}WU2Z>APRS,TCPIP,IGATE*::WU2Z     :PARM.Volts".parse().unwrap();
+        assert_eq!(registry.receive(&p), None);
+        assert_eq!(registry.definitions("WU2Z").unwrap().names, ["Volts"]);
+    }
+
+    #[test]
+    fn test_decode_unknown_station() {
+        let registry = TelemetryRegistry::new();
+        let v = registry.decode("WU2Z", &"T#001,100,,50".parse().unwrap());
+        assert_eq!(v.analog.len(), 2);
+        assert_eq!((v.analog[1].channel, v.analog[1].value), (3, 50.0));
+        assert!(v.analog[0].name.is_empty());
+        assert!(v.digital.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+use std::fmt::{self, Display, Formatter};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::ParseError;
+
+/// Timestamp as transmitted in the information field.
+///
+/// APRS timestamps are partial (no year, often no month), so they only
+/// become a point in time once resolved against a reference time, see
+/// [`Timestamp::to_system_time`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamp {
+    /// `DDHHMMz` (zulu) or `DDHHMM/` (station local time).
+    DayHourMinute { day: u8, hour: u8, minute: u8, zulu: bool },
+    /// `HHMMSSh`, always zulu.
+    HourMinuteSecond { hour: u8, minute: u8, second: u8 },
+    /// `MMDDHHMM`, zulu, used by positionless weather reports.
+    MonthDayHourMinute { month: u8, day: u8, hour: u8, minute: u8 },
+}
+
+const SECS_IN_DAY: i64 = 86_400;
+
+impl Timestamp {
+    pub(crate) fn parse(s: &str) -> Result<Timestamp, ParseError> {
+        let err = || ParseError::InvalidTimestamp(s.to_string());
+        let b = s.as_bytes();
+        if b.len() != 7 || !b[..6].iter().all(u8::is_ascii_digit) {
+            return Err(err());
+        }
+        let n = |i: usize| (b[i] - b'0') * 10 + (b[i + 1] - b'0');
+        let (a, h, m) = (n(0), n(2), n(4));
+        let ts = match b[6] {
+            b'z' => Timestamp::DayHourMinute { day: a, hour: h, minute: m, zulu: true },
+            b'/' => Timestamp::DayHourMinute { day: a, hour: h, minute: m, zulu: false },
+            b'h' => Timestamp::HourMinuteSecond { hour: a, minute: h, second: m },
+            _ => return Err(err()),
+        };
+        if ts.is_valid() { Ok(ts) } else { Err(err()) }
+    }
+
+    // `MMDDHHMM` without a type suffix
+    pub(crate) fn parse_mdhm(s: &str) -> Result<Timestamp, ParseError> {
+        let b = s.as_bytes();
+        if b.len() != 8 || !b.iter().all(u8::is_ascii_digit) {
+            return Err(ParseError::InvalidTimestamp(s.to_string()));
+        }
+        let n = |i: usize| (b[i] - b'0') * 10 + (b[i + 1] - b'0');
+        let ts = Timestamp::MonthDayHourMinute { month: n(0), day: n(2), hour: n(4), minute: n(6) };
+        if ts.is_valid() { Ok(ts) } else { Err(ParseError::InvalidTimestamp(s.to_string())) }
+    }
+
+    fn is_valid(&self) -> bool {
+        match *self {
+            Timestamp::DayHourMinute { day, hour, minute, .. } =>
+                (1..=31).contains(&day) && hour < 24 && minute < 60,
+            Timestamp::HourMinuteSecond { hour, minute, second } =>
+                hour < 24 && minute < 60 && second < 60,
+            Timestamp::MonthDayHourMinute { month, day, hour, minute } =>
+                (1..=12).contains(&month) && day >= 1 && day as u32 <= days_in_month(2000, month as u32)
+                    && hour < 24 && minute < 60,
+        }
+    }
+
+    /// Resolves the timestamp to the latest matching moment that is not
+    /// later than `reference` (allowing for a little clock skew).
+    ///
+    /// Station local time can't be resolved without knowing the station's
+    /// time zone, so it's treated as UTC.
+    pub fn to_system_time(&self, reference: SystemTime) -> Option<SystemTime> {
+        let now = reference.duration_since(UNIX_EPOCH).ok()?.as_secs() as i64;
+        let (year, month, _) = civil_from_days(now.div_euclid(SECS_IN_DAY));
+        let secs = match *self {
+            Timestamp::DayHourMinute { day, hour, minute, .. } => {
+                let time = hour as i64 * 3600 + minute as i64 * 60;
+                // Walk back from this month to the latest one that has the day
+                let previous = |&(y, m): &(i64, u32)| Some(if m > 1 { (y, m - 1) } else { (y - 1, 12) });
+                std::iter::successors(Some((year, month)), previous).take(12)
+                    .filter(|&(y, m)| day as u32 <= days_in_month(y, m))
+                    .map(|(y, m)| days_from_civil(y, m, day as u32) * SECS_IN_DAY + time)
+                    .find(|&v| v <= now + 12 * 3600)?
+            }
+            Timestamp::HourMinuteSecond { hour, minute, second } => {
+                let time = hour as i64 * 3600 + minute as i64 * 60 + second as i64;
+                let v = now.div_euclid(SECS_IN_DAY) * SECS_IN_DAY + time;
+                if v > now + 3600 { v - SECS_IN_DAY } else { v }
+            }
+            Timestamp::MonthDayHourMinute { month, day, hour, minute } => {
+                let time = hour as i64 * 3600 + minute as i64 * 60;
+                [year, year - 1].into_iter()
+                    .filter(|&y| day as u32 <= days_in_month(y, month as u32))
+                    .map(|y| days_from_civil(y, month as u32, day as u32) * SECS_IN_DAY + time)
+                    .find(|&v| v <= now + 12 * 3600)?
+            }
+        };
+        Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+    }
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Timestamp::DayHourMinute { day, hour, minute, zulu } =>
+                write!(f, "{:02}{:02}{:02}{}", day, hour, minute, if zulu { 'z' } else { '/' }),
+            Timestamp::HourMinuteSecond { hour, minute, second } =>
+                write!(f, "{:02}{:02}{:02}h", hour, minute, second),
+            Timestamp::MonthDayHourMinute { month, day, hour, minute } =>
+                write!(f, "{:02}{:02}{:02}{:02}", month, day, hour, minute),
+        }
+    }
+}
+
+// Proleptic Gregorian calendar conversions, after Howard Hinnant's
+// `days_from_civil` and `civil_from_days`
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+pub(crate) fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use super::{civil_from_days, days_from_civil};
+    use crate::{ParseError, Timestamp};
+
+    // 2024-03-05 12:00:00 UTC
+    const REFERENCE: u64 = 1_709_640_000;
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in ["092345z", "092345/", "234517h"] {
+            assert_eq!(Timestamp::parse(s).unwrap().to_string(), s);
+        }
+        assert_eq!(Timestamp::parse("092345z").unwrap(),
+            Timestamp::DayHourMinute { day: 9, hour: 23, minute: 45, zulu: true });
+        assert_eq!(Timestamp::parse("092365z"), Err(ParseError::InvalidTimestamp("092365z".to_string())));
+        assert!(Timestamp::parse("0923z").is_err());
+    }
+
+    #[test]
+    fn test_civil_days() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 3, 5), 19_787);
+        assert_eq!(civil_from_days(19_787), (2024, 3, 5));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_resolve_day_hour_minute() {
+        let reference = UNIX_EPOCH + Duration::from_secs(REFERENCE);
+        let ts = Timestamp::DayHourMinute { day: 5, hour: 10, minute: 30, zulu: true };
+        assert_eq!(ts.to_system_time(reference), Some(reference - Duration::from_secs(5400)));
+
+        // Day 20 hasn't happened yet this month, so it's February 20th
+        let ts = Timestamp::DayHourMinute { day: 20, hour: 0, minute: 0, zulu: true };
+        let expected = UNIX_EPOCH + Duration::from_secs(days_from_civil(2024, 2, 20) as u64 * 86_400);
+        assert_eq!(ts.to_system_time(reference), Some(expected));
+
+        // Neither March 31st nor February has happened, so it's January 31st
+        let ts = Timestamp::DayHourMinute { day: 31, hour: 0, minute: 0, zulu: true };
+        let expected = UNIX_EPOCH + Duration::from_secs(days_from_civil(2024, 1, 31) as u64 * 86_400);
+        assert_eq!(ts.to_system_time(reference), Some(expected));
+
+        // Day 31 after a 30-day month
+        let reference = UNIX_EPOCH + Duration::from_secs(days_from_civil(2024, 5, 1) as u64 * 86_400);
+        let expected = UNIX_EPOCH + Duration::from_secs(days_from_civil(2024, 3, 31) as u64 * 86_400);
+        assert_eq!(ts.to_system_time(reference), Some(expected));
+    }
+
+    #[test]
+    fn test_resolve_hour_minute_second() {
+        let reference = UNIX_EPOCH + Duration::from_secs(REFERENCE);
+        let ts = Timestamp::HourMinuteSecond { hour: 11, minute: 59, second: 30 };
+        assert_eq!(ts.to_system_time(reference), Some(reference - Duration::from_secs(30)));
+        let ts = Timestamp::HourMinuteSecond { hour: 23, minute: 0, second: 0 };
+        assert_eq!(ts.to_system_time(reference), Some(reference - Duration::from_secs(13 * 3600)));
+    }
+
+    #[test]
+    fn test_month_day_hour_minute() {
+        let ts = Timestamp::parse_mdhm("10090556").unwrap();
+        assert_eq!(ts, Timestamp::MonthDayHourMinute { month: 10, day: 9, hour: 5, minute: 56 });
+        assert_eq!(ts.to_string(), "10090556");
+        assert!(Timestamp::parse_mdhm("13090556").is_err());
+        assert!(Timestamp::parse_mdhm("0230055").is_err());
+
+        // October hasn't come yet in March, so it's last year's
+        let reference = UNIX_EPOCH + Duration::from_secs(REFERENCE);
+        let expected = days_from_civil(2023, 10, 9) as u64 * 86_400 + 5 * 3600 + 56 * 60;
+        assert_eq!(ts.to_system_time(reference), Some(UNIX_EPOCH + Duration::from_secs(expected)));
+    }
+}
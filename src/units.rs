@@ -88,6 +88,32 @@ impl From<Feet> for Meters {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Miles(pub f32);
+impl Miles {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Miles {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}mi", self.0)
+    }
+}
+const METERS_IN_MILE : f32 = 1609.344;
+impl From<Meters> for Miles {
+    fn from(v : Meters) -> Self {
+        Miles(v.0 / METERS_IN_MILE)
+    }
+}
+impl From<Miles> for Meters {
+    fn from(v : Miles) -> Self {
+        Meters(v.0 * METERS_IN_MILE)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)] pub struct Knots(pub f32);
 impl Knots {
     pub fn as_f32(&self) -> f32 {
@@ -225,10 +251,217 @@ impl From<Celsius> for Fahrenheits {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Inches(pub f32);
+impl Inches {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Inches {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}in", self.0)
+    }
+}
+
+// Rainfall unit of weather reports
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct HundredthsOfInch(pub f32);
+impl HundredthsOfInch {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for HundredthsOfInch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}in", self.0 / 100.0)
+    }
+}
+const HUNDREDTHS_IN_INCH : f32 = 100.0;
+impl From<Inches> for HundredthsOfInch {
+    fn from(v : Inches) -> Self {
+        HundredthsOfInch(v.0 * HUNDREDTHS_IN_INCH)
+    }
+}
+impl From<HundredthsOfInch> for Inches {
+    fn from(v : HundredthsOfInch) -> Self {
+        Inches(v.0 / HUNDREDTHS_IN_INCH)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Millimeters(pub f32);
+impl Millimeters {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Millimeters {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}mm", self.0)
+    }
+}
+const MM_IN_INCH : f32 = 25.4;
+impl From<Inches> for Millimeters {
+    fn from(v : Inches) -> Self {
+        Millimeters(v.0 * MM_IN_INCH)
+    }
+}
+impl From<Millimeters> for Inches {
+    fn from(v : Millimeters) -> Self {
+        Inches(v.0 / MM_IN_INCH)
+    }
+}
+impl From<HundredthsOfInch> for Millimeters {
+    fn from(v : HundredthsOfInch) -> Self {
+        Millimeters::from(Inches::from(v))
+    }
+}
+impl From<Millimeters> for HundredthsOfInch {
+    fn from(v : Millimeters) -> Self {
+        HundredthsOfInch::from(Inches::from(v))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Hectopascals(pub f32);
+impl Hectopascals {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Hectopascals {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}hPa", self.0)
+    }
+}
+pub type Millibars = Hectopascals;
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct InchesOfMercury(pub f32);
+impl InchesOfMercury {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for InchesOfMercury {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}inHg", self.0)
+    }
+}
+const HPA_IN_INHG : f32 = 33.8639;
+impl From<InchesOfMercury> for Hectopascals {
+    fn from(v : InchesOfMercury) -> Self {
+        Hectopascals(v.0 * HPA_IN_INHG)
+    }
+}
+impl From<Hectopascals> for InchesOfMercury {
+    fn from(v : Hectopascals) -> Self {
+        InchesOfMercury(v.0 / HPA_IN_INHG)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Percent(pub f32);
+impl Percent {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Percent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Watts(pub f32);
+impl Watts {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Watts {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}W", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct WattsPerSquareMeter(pub f32);
+impl WattsPerSquareMeter {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for WattsPerSquareMeter {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}W/m²", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Hertz(pub f32);
+impl Hertz {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Hertz {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}Hz", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)] pub struct Megahertz(pub f32);
+impl Megahertz {
+    pub fn as_f32(&self) -> f32 {
+        self.0
+    }
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64
+    }
+}
+impl Display for Megahertz {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}\u{202f}MHz", self.0)
+    }
+}
+const HZ_IN_MHZ : f32 = 1_000_000.0;
+impl From<Hertz> for Megahertz {
+    fn from(v : Hertz) -> Self {
+        Megahertz(v.0 / HZ_IN_MHZ)
+    }
+}
+impl From<Megahertz> for Hertz {
+    fn from(v : Megahertz) -> Self {
+        Hertz(v.0 * HZ_IN_MHZ)
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
-    use super::{Meters, Feet, Knots, MetersPerSecond, KilometersPerHour, MilesPerHour, Celsius, Fahrenheits};
+    use super::{Meters, Feet, Miles, Knots, MetersPerSecond, KilometersPerHour, MilesPerHour, Celsius, Fahrenheits,
+        Inches, HundredthsOfInch, Millimeters, Hectopascals, Millibars, InchesOfMercury};
     const EPSILON : f32 = 0.0001;
 
     #[test]
@@ -243,6 +476,12 @@ mod tests {
          assert_abs_diff_eq!(Feet::from(Meters(30.48)).0, Feet(100.0).0, epsilon=EPSILON);
     }
 
+    #[test]
+    fn test_miles_to_meters() {
+         assert_abs_diff_eq!(Meters::from(Miles(0.5)).0, Meters(804.672).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(Miles::from(Meters(804.672)).0, Miles(0.5).0, epsilon=EPSILON);
+    }
+
     #[test]
     fn test_knots_to_mps() {
          assert_abs_diff_eq!(MetersPerSecond::from(Knots(20.0)).0, MetersPerSecond(10.2889).0, epsilon=EPSILON);
@@ -279,6 +518,26 @@ mod tests {
          assert_abs_diff_eq!(Fahrenheits::from(Celsius(26.6667)).0, Fahrenheits(80.0).0, epsilon=EPSILON);
     }
 
+    #[test]
+    fn test_hundredths_to_inches() {
+         assert_abs_diff_eq!(Inches::from(HundredthsOfInch(125.0)).0, Inches(1.25).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(HundredthsOfInch::from(Inches(1.25)).0, HundredthsOfInch(125.0).0, epsilon=EPSILON);
+    }
+
+    #[test]
+    fn test_rain_to_millimeters() {
+         assert_abs_diff_eq!(Millimeters::from(Inches(2.0)).0, Millimeters(50.8).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(Inches::from(Millimeters(50.8)).0, Inches(2.0).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(Millimeters::from(HundredthsOfInch(10.0)).0, Millimeters(2.54).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(HundredthsOfInch::from(Millimeters(2.54)).0, HundredthsOfInch(10.0).0, epsilon=EPSILON);
+    }
+
+    #[test]
+    fn test_hpa_to_inhg() {
+         assert_abs_diff_eq!(InchesOfMercury::from(Hectopascals(1013.25)).0, InchesOfMercury(29.9213).0, epsilon=EPSILON);
+         assert_abs_diff_eq!(Millibars::from(InchesOfMercury(29.9213)).0, Hectopascals(1013.25).0, epsilon=0.01);
+    }
+
     #[test]
     fn into_itself() {
         let x : Meters = Meters(5.0).into();
@@ -0,0 +1,274 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::{Degrees, Fahrenheits, Hectopascals, HundredthsOfInch, Inches, MilesPerHour, ParseError, Percent,
+    Timestamp, WattsPerSquareMeter};
+
+/// Weather data of APRS101 chapter 12, in the units it's transmitted in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Weather {
+    pub wind_direction: Option<Degrees>,
+    /// Sustained one-minute wind speed.
+    pub wind_speed: Option<MilesPerHour>,
+    /// Peak wind speed in the last 5 minutes.
+    pub wind_gust: Option<MilesPerHour>,
+    pub temperature: Option<Fahrenheits>,
+    pub rain_last_hour: Option<HundredthsOfInch>,
+    pub rain_last_24h: Option<HundredthsOfInch>,
+    pub rain_since_midnight: Option<HundredthsOfInch>,
+    pub humidity: Option<Percent>,
+    /// Barometric pressure, corrected to sea level.
+    pub pressure: Option<Hectopascals>,
+    pub luminosity: Option<WattsPerSquareMeter>,
+    /// Snowfall in the last 24 hours.
+    pub snowfall: Option<Inches>,
+    /// Raw rain counter of the station.
+    pub rain_counter: Option<u16>,
+}
+
+impl Weather {
+    // Parses the `g`, `t`, `r`... fields at the start of `s`, up to the first
+    // one that isn't recognised. In positionless reports `c` and `s` come
+    // first and `s` is the wind speed, later it's the snowfall.
+    pub(crate) fn parse_fields(s: &str, positionless: bool) -> (Weather, &str) {
+        let mut weather = Weather::default();
+        let mut wind_speed_next = positionless;
+        let mut rest = s;
+        while let Some(&tag) = rest.as_bytes().first() {
+            let len = match tag {
+                b'c' | b's' | b'g' | b't' | b'r' | b'p' | b'P' | b'L' | b'l' | b'#' => 3,
+                b'h' => 2,
+                b'b' => 5,
+                _ => break,
+            };
+            let Some(raw) = rest.get(1..=len) else { break };
+            let Ok(value) = parse_value(raw) else { break };
+            match tag {
+                b'c' => weather.wind_direction = value.map(Degrees),
+                b's' if wind_speed_next => weather.wind_speed = value.map(MilesPerHour),
+                b's' => weather.snowfall = value.map(Inches),
+                b'g' => weather.wind_gust = value.map(MilesPerHour),
+                b't' => weather.temperature = value.map(Fahrenheits),
+                b'r' => weather.rain_last_hour = value.map(HundredthsOfInch),
+                b'p' => weather.rain_last_24h = value.map(HundredthsOfInch),
+                b'P' => weather.rain_since_midnight = value.map(HundredthsOfInch),
+                // `00` stands for 100%
+                b'h' => weather.humidity = value.map(|v| Percent(if v == 0.0 { 100.0 } else { v })),
+                b'b' => weather.pressure = value.map(|v| Hectopascals(v / 10.0)),
+                b'L' => weather.luminosity = value.map(WattsPerSquareMeter),
+                b'l' => weather.luminosity = value.map(|v| WattsPerSquareMeter(v + 1000.0)),
+                _ => weather.rain_counter = value.map(|v| v as u16),
+            }
+            if tag != b'c' {
+                wind_speed_next = false;
+            }
+            rest = &rest[len + 1..];
+        }
+        (weather, rest)
+    }
+
+    // Weather following a `_` symbol, `ddd/sss` wind then the other fields
+    pub(crate) fn parse_after_symbol(s: &str) -> (Weather, &str) {
+        let wind = match (s.get(..3), s.as_bytes().get(3), s.get(4..7)) {
+            (Some(dir), Some(b'/'), Some(speed)) => Some((parse_value(dir), parse_value(speed))),
+            _ => None,
+        };
+        match wind {
+            Some((Ok(dir), Ok(speed))) => {
+                let (mut weather, rest) = Weather::parse_fields(&s[7..], false);
+                weather.wind_direction = dir.map(Degrees);
+                weather.wind_speed = speed.map(MilesPerHour);
+                (weather, rest)
+            }
+            _ => Weather::parse_fields(s, false),
+        }
+    }
+
+    // Tag, value and width of the fields after the wind
+    fn fields(&self) -> [(char, Option<f32>, usize); 10] {
+        [
+            ('g', self.wind_gust.map(|v| v.0.round()), 3),
+            ('t', self.temperature.map(|v| v.0.round()), 3),
+            ('r', self.rain_last_hour.map(|v| v.0.round()), 3),
+            ('p', self.rain_last_24h.map(|v| v.0.round()), 3),
+            ('P', self.rain_since_midnight.map(|v| v.0.round()), 3),
+            ('h', self.humidity.map(|v| v.0.round() % 100.0), 2),
+            ('b', self.pressure.map(|v| (v.0 * 10.0).round()), 5),
+            match self.luminosity {
+                Some(v) if v.0 >= 1000.0 => ('l', Some((v.0 - 1000.0).round()), 3),
+                v => ('L', v.map(|v| v.0.round()), 3),
+            },
+            ('s', self.snowfall.map(|v| v.0.round()), 3),
+            ('#', self.rain_counter.map(f32::from), 3),
+        ]
+    }
+
+    // `ddd/sss` wind and the other fields, as written after a `_` symbol
+    pub(crate) fn fmt_after_symbol(&self, f: &mut Formatter) -> fmt::Result {
+        for (value, separator) in [(self.wind_direction.map(|v| v.0), "/"), (self.wind_speed.map(|v| v.0), "")] {
+            match value {
+                Some(v) => write!(f, "{:03}{}", v.round(), separator)?,
+                None => write!(f, "...{}", separator)?,
+            }
+        }
+        self.fmt_fields(f)
+    }
+
+    // Gust and temperature are mandatory, the rest is only written when known
+    pub(crate) fn fmt_fields(&self, f: &mut Formatter) -> fmt::Result {
+        for (i, (tag, value, width)) in self.fields().into_iter().enumerate() {
+            if i < 2 || value.is_some() {
+                write_field(f, tag, value, width)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Only the known fields after the wind, as found in comments
+    pub(crate) fn fmt_known_fields(&self, f: &mut Formatter) -> fmt::Result {
+        for (tag, value, width) in self.fields() {
+            if value.is_some() {
+                write_field(f, tag, value, width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Integer or a run of dots or spaces for a missing value
+fn parse_value(s: &str) -> Result<Option<f32>, ()> {
+    if s.bytes().all(|c| c == b'.' || c == b' ') {
+        Ok(None)
+    } else if s.bytes().all(|c| c.is_ascii_digit() || c == b'-') {
+        s.parse().map(Some).map_err(|_| ())
+    } else {
+        Err(())
+    }
+}
+
+fn write_field(f: &mut Formatter, tag: char, value: Option<f32>, width: usize) -> fmt::Result {
+    match value {
+        Some(v) => write!(f, "{}{:0width$}", tag, v, width = width),
+        None => write!(f, "{}{:.<width$}", tag, "", width = width),
+    }
+}
+
+/// Positionless weather report, data type identifier `_`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherReport {
+    /// `MMDDHHMM` zulu timestamp.
+    pub timestamp: Timestamp,
+    pub weather: Weather,
+    /// Software type, unit type and any text following the weather data.
+    pub comment: String,
+}
+
+impl FromStr for WeatherReport {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.starts_with('_') {
+            return Err(ParseError::InvalidWeather(s.to_string()));
+        }
+        let ts = s.get(1..9).ok_or_else(|| ParseError::InvalidTimestamp(s[1..].to_string()))?;
+        let timestamp = Timestamp::parse_mdhm(ts)?;
+        let (weather, rest) = Weather::parse_fields(&s[9..], true);
+        Ok(WeatherReport { timestamp, weather, comment: rest.to_string() })
+    }
+}
+
+impl Display for WeatherReport {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "_{}", self.timestamp)?;
+        write_field(f, 'c', self.weather.wind_direction.map(|v| v.0.round()), 3)?;
+        write_field(f, 's', self.weather.wind_speed.map(|v| v.0.round()), 3)?;
+        self.weather.fmt_fields(f)?;
+        write!(f, "{}", self.comment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Degrees, Fahrenheits, Hectopascals, HundredthsOfInch, Inches, MilesPerHour, ParseError, Percent,
+        Timestamp, WattsPerSquareMeter, Weather, WeatherReport};
+
+    #[test]
+    fn test_parse_positionless() {
+        let v: WeatherReport = "_10090556c220s004g005t077r001p010P020h50b09900wRSW".parse().unwrap();
+        assert_eq!(v.timestamp, Timestamp::MonthDayHourMinute { month: 10, day: 9, hour: 5, minute: 56 });
+        assert_eq!(v.weather.wind_direction, Some(Degrees(220.0)));
+        assert_eq!(v.weather.wind_speed, Some(MilesPerHour(4.0)));
+        assert_eq!(v.weather.wind_gust, Some(MilesPerHour(5.0)));
+        assert_eq!(v.weather.temperature, Some(Fahrenheits(77.0)));
+        assert_eq!(v.weather.rain_last_hour, Some(HundredthsOfInch(1.0)));
+        assert_eq!(v.weather.rain_last_24h, Some(HundredthsOfInch(10.0)));
+        assert_eq!(v.weather.rain_since_midnight, Some(HundredthsOfInch(20.0)));
+        assert_eq!(v.weather.humidity, Some(Percent(50.0)));
+        assert_eq!(v.weather.pressure, Some(Hectopascals(990.0)));
+        assert_eq!(v.weather.snowfall, None);
+        assert_eq!(v.comment, "wRSW");
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let (v, rest) = Weather::parse_fields("c...s...g...t-05h00b10132L999s002#123 Home", true);
+        assert_eq!(v.wind_direction, None);
+        assert_eq!(v.wind_speed, None);
+        assert_eq!(v.temperature, Some(Fahrenheits(-5.0)));
+        assert_eq!(v.humidity, Some(Percent(100.0)));
+        assert_abs_diff_eq!(v.pressure.unwrap().0, 1013.2, epsilon = 0.001);
+        assert_eq!(v.luminosity, Some(WattsPerSquareMeter(999.0)));
+        assert_eq!(v.snowfall.map(|v| v.0), Some(2.0));
+        assert_eq!(v.rain_counter, Some(123));
+        assert_eq!(rest, " Home");
+
+        let (v, _) = Weather::parse_fields("l123", false);
+        assert_eq!(v.luminosity, Some(WattsPerSquareMeter(1123.0)));
+    }
+
+    #[test]
+    fn test_parse_after_symbol() {
+        let (v, rest) = Weather::parse_after_symbol("220/004g005t077r000p000P000h50b09900wRSW");
+        assert_eq!(v.wind_direction, Some(Degrees(220.0)));
+        assert_eq!(v.wind_speed, Some(MilesPerHour(4.0)));
+        assert_eq!(v.temperature, Some(Fahrenheits(77.0)));
+        assert_eq!(v.rain_last_hour, Some(HundredthsOfInch(0.0)));
+        assert_eq!(rest, "wRSW");
+
+        let (v, rest) = Weather::parse_after_symbol(".../...g...t050");
+        assert_eq!(v.wind_direction, None);
+        assert_eq!(v.temperature, Some(Fahrenheits(50.0)));
+        assert_eq!(rest, "");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!("10090556c220".parse::<WeatherReport>(), Err(ParseError::InvalidWeather("10090556c220".to_string())));
+        assert!("_1009c220s004".parse::<WeatherReport>().is_err());
+
+        // Only integers are values, the rest is left as the comment
+        for s in ["tNaN", "tinf", "t1.5", "t+12"] {
+            assert_eq!(Weather::parse_fields(s, false), (Weather::default(), s));
+        }
+    }
+
+    #[test]
+    fn test_all_missing() {
+        let v: WeatherReport = "_10090556c...s...g...t...".parse().unwrap();
+        assert_eq!(v.weather, Weather::default());
+        assert_eq!(v.to_string(), "_10090556c...s...g...t...");
+        assert_eq!("_10090556wRSW".parse::<WeatherReport>().unwrap().comment, "wRSW");
+    }
+
+    #[test]
+    fn test_display() {
+        for s in ["_10090556c220s004g005t077r001p010P020h50b09900wRSW", "_10090556c...s...g...t-05h00L999s002#123",
+                  "_01011200c090s010g015t032l100"] {
+            assert_eq!(s.parse::<WeatherReport>().unwrap().to_string(), s);
+        }
+
+        let mut v: WeatherReport = "_01011200c090s010g015t032".parse().unwrap();
+        v.weather.snowfall = Some(Inches(1.5));
+        assert_eq!(v.to_string(), "_01011200c090s010g015t032s002");
+    }
+}